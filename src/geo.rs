@@ -1,6 +1,27 @@
-use crate::measure::Angle;
+use crate::measure::{Angle, Direction};
+use std::f64::consts::PI;
 use std::fmt::{Debug, Formatter};
 
+/// Mean radius of the Earth, in nautical miles.
+const EARTH_RADIUS_NM: f64 = 3_440.065;
+const METERS_PER_NM: f64 = 1_852.0;
+
+/// WGS84 ellipsoid semi-major axis and flattening.
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// How the shape of the Earth is modeled when measuring distances.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum DistanceModel {
+    /// Great-circle distance on a spherical Earth (haversine). Fast, and accurate to within 1%.
+    #[default]
+    Sphere,
+    /// Geodesic distance on the WGS84 ellipsoid (Vincenty's inverse formula), accurate to well
+    /// under a meter. Falls back to [`DistanceModel::Sphere`] for nearly antipodal points, where
+    /// the iteration fails to converge.
+    Vincenty,
+}
+
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub enum LatitudeHemisphere {
     NORTH,
@@ -16,12 +37,63 @@ impl LatitudeHemisphere {
     }
 }
 
+/// Prefer the accessor methods to the fields, which will become private in a future release so
+/// that the range can be enforced.
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub struct Latitude {
     pub angle: Angle,
     pub hemisphere: LatitudeHemisphere,
 }
 
+impl Latitude {
+    pub fn angle(&self) -> Angle {
+        self.angle
+    }
+
+    pub fn hemisphere(&self) -> LatitudeHemisphere {
+        self.hemisphere
+    }
+
+    fn from_signed_degrees(degrees: f64) -> Self {
+        Self {
+            angle: Angle::with_decimal_degrees(degrees.abs()),
+            hemisphere: if degrees < 0.0 {
+                LatitudeHemisphere::SOUTH
+            } else {
+                LatitudeHemisphere::NORTH
+            },
+        }
+    }
+
+    /// Decimal degrees north of the equator, negative in the southern hemisphere. The equator is
+    /// `0.0` whichever hemisphere it is labelled with, never `-0.0`.
+    pub fn signed_degrees_north(&self) -> f64 {
+        match self.hemisphere {
+            LatitudeHemisphere::NORTH => self.angle.decimal_degrees(),
+            LatitudeHemisphere::SOUTH if self.angle.decimal_degrees() == 0.0 => 0.0,
+            LatitudeHemisphere::SOUTH => -self.angle.decimal_degrees(),
+        }
+    }
+
+    /// The same parallel with the equator always labelled north, so 00°00'S becomes 00°00'N and
+    /// compares equal to it. Any other latitude is returned unchanged.
+    pub fn normalized(&self) -> Latitude {
+        Self::from_signed_degrees(self.signed_degrees_north())
+    }
+
+    /// The HDOB latitude group, degrees and minutes with the hemisphere (`2006N`). Seconds are
+    /// dropped, as HDOB has no field for them.
+    pub fn to_hdob(&self) -> String {
+        let (d, m, _) = self.angle.degrees_minutes_seconds();
+        format!("{:02}{:02}{}", d, m, self.hemisphere.short())
+    }
+
+    /// Whether `other` is within `tolerance` decimal degrees of this latitude.
+    pub fn approx_eq(&self, other: &Latitude, tolerance: f64) -> bool {
+        (self.signed_degrees_north() - other.signed_degrees_north()).abs() <= tolerance
+    }
+}
+
 impl Debug for Latitude {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}{}", self.angle, self.hemisphere.short())
@@ -43,26 +115,465 @@ impl LongitudeHemisphere {
     }
 }
 
+/// Prefer the accessor methods to the fields, which will become private in a future release so
+/// that the range can be enforced.
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub struct Longitude {
     pub angle: Angle,
     pub hemisphere: LongitudeHemisphere,
 }
 
+impl Longitude {
+    pub fn angle(&self) -> Angle {
+        self.angle
+    }
+
+    pub fn hemisphere(&self) -> LongitudeHemisphere {
+        self.hemisphere
+    }
+
+    /// Wraps degrees east into (-180, 180] before splitting off the hemisphere.
+    fn from_signed_degrees(degrees: f64) -> Self {
+        let degrees = (degrees + 180.0).rem_euclid(360.0) - 180.0;
+        let degrees = if degrees == -180.0 { 180.0 } else { degrees };
+        Self {
+            angle: Angle::with_decimal_degrees(degrees.abs()),
+            hemisphere: if degrees < 0.0 {
+                LongitudeHemisphere::WEST
+            } else {
+                LongitudeHemisphere::EAST
+            },
+        }
+    }
+
+    /// Decimal degrees east of the prime meridian, negative in the western hemisphere. Not
+    /// wrapped; see [`Longitude::normalized`].
+    pub fn signed_degrees_east(&self) -> f64 {
+        match self.hemisphere {
+            LongitudeHemisphere::EAST => self.angle.decimal_degrees(),
+            LongitudeHemisphere::WEST => -self.angle.decimal_degrees(),
+        }
+    }
+
+    /// The same meridian expressed within (-180°, 180°], so 190°E becomes 170°W and 180°W
+    /// becomes 180°E.
+    pub fn normalized(&self) -> Longitude {
+        Self::from_signed_degrees(self.signed_degrees_east())
+    }
+
+    /// The HDOB longitude group, degrees and minutes with the hemisphere (`06141W`). Seconds
+    /// are dropped, as HDOB has no field for them.
+    pub fn to_hdob(&self) -> String {
+        let (d, m, _) = self.angle.degrees_minutes_seconds();
+        format!("{:03}{:02}{}", d, m, self.hemisphere.short())
+    }
+
+    /// Whether `other` is within `tolerance` decimal degrees of this longitude, measured the
+    /// short way around so that 179.9°E and 179.9°W are 0.2° apart.
+    pub fn approx_eq(&self, other: &Longitude, tolerance: f64) -> bool {
+        self.degrees_to(other).abs() <= tolerance
+    }
+
+    /// Degrees east from `self` to `other`, going the short way around and so crossing the
+    /// antimeridian where that is shorter.
+    fn degrees_to(&self, other: &Longitude) -> f64 {
+        (other.signed_degrees_east() - self.signed_degrees_east() + 540.0).rem_euclid(360.0) - 180.0
+    }
+}
+
 impl Debug for Longitude {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}{}", self.angle, self.hemisphere.short())
     }
 }
 
+/// Prefer the accessor methods to the fields, which will become private in a future release.
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub struct Coordinate {
     pub latitude: Latitude,
     pub longitude: Longitude,
 }
 
+impl Coordinate {
+    pub fn latitude(&self) -> Latitude {
+        self.latitude
+    }
+
+    pub fn longitude(&self) -> Longitude {
+        self.longitude
+    }
+
+    /// Signed decimal degrees as `(latitude, longitude)`, north and east positive.
+    pub fn as_lat_lon(&self) -> (f64, f64) {
+        (
+            self.latitude.signed_degrees_north(),
+            self.longitude.signed_degrees_east(),
+        )
+    }
+
+    /// Signed decimal degrees as `[longitude, latitude]`, the order of GeoJSON and most GIS
+    /// libraries.
+    pub fn as_lon_lat(&self) -> [f64; 2] {
+        [
+            self.longitude.signed_degrees_east(),
+            self.latitude.signed_degrees_north(),
+        ]
+    }
+
+    /// Great-circle distance to `other`, in nautical miles.
+    pub fn distance_to(&self, other: &Coordinate) -> f64 {
+        self.distance_to_with(other, DistanceModel::Sphere)
+    }
+
+    /// Whether `other` is within `tol_nm` nautical miles (great-circle) of this coordinate.
+    pub fn approx_eq(&self, other: &Coordinate, tol_nm: f64) -> bool {
+        self.distance_to(other) <= tol_nm
+    }
+
+    /// Distance to `other` under the given Earth model, in nautical miles.
+    pub fn distance_to_with(&self, other: &Coordinate, model: DistanceModel) -> f64 {
+        match model {
+            DistanceModel::Sphere => self.haversine_nm(other),
+            DistanceModel::Vincenty => self
+                .vincenty_meters(other)
+                .map(|m| m / METERS_PER_NM)
+                .unwrap_or_else(|| self.haversine_nm(other)),
+        }
+    }
+
+    fn haversine_nm(&self, other: &Coordinate) -> f64 {
+        let lat1 = self.latitude.signed_degrees_north().to_radians();
+        let lat2 = other.latitude.signed_degrees_north().to_radians();
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.longitude.signed_degrees_east() - self.longitude.signed_degrees_east())
+            .to_radians();
+        let h = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_NM * h.sqrt().asin()
+    }
+
+    /// Vincenty's inverse formula on the WGS84 ellipsoid, or `None` if it fails to converge.
+    fn vincenty_meters(&self, other: &Coordinate) -> Option<f64> {
+        let b = WGS84_A * (1.0 - WGS84_F);
+        let l = self.longitude.degrees_to(&other.longitude).to_radians();
+        let u1 = ((1.0 - WGS84_F) * self.latitude.signed_degrees_north().to_radians().tan()).atan();
+        let u2 =
+            ((1.0 - WGS84_F) * other.latitude.signed_degrees_north().to_radians().tan()).atan();
+        let (sin_u1, cos_u1) = u1.sin_cos();
+        let (sin_u2, cos_u2) = u2.sin_cos();
+
+        let mut lambda = l;
+        for _ in 0..200 {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+                + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+            .sqrt();
+            if sin_sigma == 0.0 {
+                // Coincident points
+                return Some(0.0);
+            }
+            let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            let sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            let cos_sq_alpha = 1.0 - sin_alpha * sin_alpha;
+            let cos_2sigma_m = if cos_sq_alpha == 0.0 {
+                // Both points on the equator
+                0.0
+            } else {
+                cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha
+            };
+            let c = WGS84_F / 16.0 * cos_sq_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos_sq_alpha));
+
+            let previous = lambda;
+            lambda = l
+                + (1.0 - c)
+                    * WGS84_F
+                    * sin_alpha
+                    * (sigma
+                        + c * sin_sigma
+                            * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
+            if lambda.abs() > PI {
+                return None;
+            }
+
+            if (lambda - previous).abs() < 1e-12 {
+                let u_sq = cos_sq_alpha * (WGS84_A.powi(2) - b.powi(2)) / b.powi(2);
+                let big_a = 1.0
+                    + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+                let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+                let delta_sigma = big_b
+                    * sin_sigma
+                    * (cos_2sigma_m
+                        + big_b / 4.0
+                            * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))
+                                - big_b / 6.0
+                                    * cos_2sigma_m
+                                    * (-3.0 + 4.0 * sin_sigma.powi(2))
+                                    * (-3.0 + 4.0 * cos_2sigma_m.powi(2))));
+                return Some(b * big_a * (sigma - delta_sigma));
+            }
+        }
+        None
+    }
+
+    /// The point `fraction` of the way from `self` to `other`, interpolating latitude and
+    /// longitude linearly. Only suitable for the short hops between consecutive observations.
+    pub fn interpolate(&self, other: &Coordinate, fraction: f64) -> Coordinate {
+        let lat = self.latitude.signed_degrees_north();
+        let d_lat = other.latitude.signed_degrees_north() - lat;
+        let lon = self.longitude.signed_degrees_east();
+        let d_lon = self.longitude.degrees_to(&other.longitude);
+        Coordinate {
+            latitude: Latitude::from_signed_degrees(lat + d_lat * fraction),
+            longitude: Longitude::from_signed_degrees(lon + d_lon * fraction),
+        }
+    }
+
+    /// The point halfway between `self` and `other`, by the same rule as [`Self::interpolate`].
+    pub fn midpoint(&self, other: &Coordinate) -> Coordinate {
+        self.interpolate(other, 0.5)
+    }
+
+    /// The initial great-circle bearing from `self` toward `other`.
+    pub fn bearing_to(&self, other: &Coordinate) -> Direction {
+        let lat1 = self.latitude.signed_degrees_north().to_radians();
+        let lat2 = other.latitude.signed_degrees_north().to_radians();
+        let d_lon = self.longitude.degrees_to(&other.longitude).to_radians();
+        let y = d_lon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lon.cos();
+        Direction::from_degrees(y.atan2(x).to_degrees())
+    }
+}
+
+/// The smallest latitude/longitude box enclosing a set of coordinates.
+///
+/// If the box straddles the antimeridian, `west` lies east of `east` numerically (e.g. west
+/// 179°E, east 179°W).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BoundingBox {
+    pub south: Latitude,
+    pub north: Latitude,
+    pub west: Longitude,
+    pub east: Longitude,
+}
+
+impl BoundingBox {
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.west.signed_degrees_east() > self.east.signed_degrees_east()
+    }
+}
+
+/// The smallest box enclosing every coordinate, or `None` if there are none. Longitudes span
+/// the narrowest arc covering all points, which may cross the antimeridian.
+pub fn bounding_box<'a>(
+    coordinates: impl IntoIterator<Item = &'a Coordinate>,
+) -> Option<BoundingBox> {
+    let coordinates: Vec<&Coordinate> = coordinates.into_iter().collect();
+    let south = coordinates.iter().map(|c| c.latitude).min_by(|a, b| {
+        a.signed_degrees_north()
+            .total_cmp(&b.signed_degrees_north())
+    })?;
+    let north = coordinates.iter().map(|c| c.latitude).max_by(|a, b| {
+        a.signed_degrees_north()
+            .total_cmp(&b.signed_degrees_north())
+    })?;
+
+    let mut longitudes: Vec<Longitude> = coordinates
+        .iter()
+        .map(|c| c.longitude.normalized())
+        .collect();
+    longitudes.sort_by(|a, b| a.signed_degrees_east().total_cmp(&b.signed_degrees_east()));
+
+    // The box covers everything but the widest empty arc between neighbouring longitudes
+    let eastward = |from: &Longitude, to: &Longitude| {
+        (to.signed_degrees_east() - from.signed_degrees_east()).rem_euclid(360.0)
+    };
+    let n = longitudes.len();
+    let (east, west) = (0..n)
+        .map(|i| (longitudes[i], longitudes[(i + 1) % n]))
+        .max_by(|(e1, w1), (e2, w2)| eastward(e1, w1).total_cmp(&eastward(e2, w2)))?;
+
+    Some(BoundingBox {
+        south,
+        north,
+        west,
+        east,
+    })
+}
+
+/// A point with longitude as `x` and latitude as `y`, in signed decimal degrees.
+#[cfg(feature = "geo-types")]
+impl From<Coordinate> for geo_types::Point<f64> {
+    fn from(coordinate: Coordinate) -> Self {
+        let [lon, lat] = coordinate.as_lon_lat();
+        geo_types::Point::new(lon, lat)
+    }
+}
+
 impl Debug for Coordinate {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "({:?}, {:?})", self.latitude, self.longitude)
     }
 }
+
+#[cfg(test)]
+fn coordinate(lat: f64, lon: f64) -> Coordinate {
+    Coordinate {
+        latitude: Latitude::from_signed_degrees(lat),
+        longitude: Longitude::from_signed_degrees(lon),
+    }
+}
+
+#[test]
+fn test_accessors() {
+    let c = coordinate(-20.5, 170.25);
+    assert_eq!(c.latitude, c.latitude());
+    assert_eq!(c.longitude, c.longitude());
+    assert_eq!(Angle::with_decimal_degrees(20.5), c.latitude().angle());
+    assert_eq!(LatitudeHemisphere::SOUTH, c.latitude().hemisphere());
+    assert_eq!(Angle::with_decimal_degrees(170.25), c.longitude().angle());
+    assert_eq!(LongitudeHemisphere::EAST, c.longitude().hemisphere());
+}
+
+#[test]
+fn test_as_lat_lon() {
+    let c = coordinate(-20.5, 170.25);
+    assert_eq!((-20.5, 170.25), c.as_lat_lon());
+    assert_eq!([170.25, -20.5], c.as_lon_lat());
+
+    #[cfg(feature = "geo-types")]
+    {
+        let point = geo_types::Point::from(c);
+        assert_eq!((170.25, -20.5), (point.x(), point.y()));
+    }
+}
+
+#[test]
+fn test_signed_degrees() {
+    let southwest = Coordinate {
+        latitude: Latitude {
+            angle: Angle::with_degrees_minutes_seconds(12, 30, 0),
+            hemisphere: LatitudeHemisphere::SOUTH,
+        },
+        longitude: Longitude {
+            angle: Angle::with_degrees_minutes_seconds(77, 15, 0),
+            hemisphere: LongitudeHemisphere::WEST,
+        },
+    };
+    assert_eq!(-12.5, southwest.latitude.signed_degrees_north());
+    assert_eq!(-77.25, southwest.longitude.signed_degrees_east());
+
+    let northeast = coordinate(12.5, 77.25);
+    assert_eq!(12.5, northeast.latitude.signed_degrees_north());
+    assert_eq!(77.25, northeast.longitude.signed_degrees_east());
+}
+
+#[test]
+fn test_equator() {
+    let north = Latitude {
+        angle: Angle::with_degrees_minutes_seconds(0, 0, 0),
+        hemisphere: LatitudeHemisphere::NORTH,
+    };
+    let south = Latitude {
+        hemisphere: LatitudeHemisphere::SOUTH,
+        ..north
+    };
+    assert_ne!(north, south);
+    assert_eq!(north, south.normalized());
+    assert_eq!(north, north.normalized());
+    assert!(south.signed_degrees_north().is_sign_positive());
+    assert!(south.approx_eq(&north, 0.0));
+    assert_eq!("0000S", south.to_hdob());
+
+    let just_south = Latitude {
+        angle: Angle::with_degrees_minutes_seconds(0, 1, 0),
+        ..south
+    };
+    assert_eq!(just_south, just_south.normalized());
+    assert!(just_south.signed_degrees_north() < 0.0);
+}
+
+#[test]
+fn test_distance_to() {
+    // One degree of longitude along the equator, and the first degree of meridian arc
+    let origin = coordinate(0.0, 0.0);
+    let east = origin.distance_to_with(&coordinate(0.0, 1.0), DistanceModel::Vincenty);
+    assert!((east * METERS_PER_NM - 111_319.491).abs() < 1.0);
+    let north = origin.distance_to_with(&coordinate(1.0, 0.0), DistanceModel::Vincenty);
+    assert!((north * METERS_PER_NM - 110_574.389).abs() < 1.0);
+
+    // The sphere is within a percent
+    let sphere = origin.distance_to(&coordinate(1.0, 0.0));
+    assert!((sphere - north).abs() / north < 0.01);
+
+    // Nearly antipodal: Vincenty fails to converge and falls back to the sphere
+    let antipode = coordinate(0.5, 179.7);
+    assert_eq!(None, origin.vincenty_meters(&antipode));
+    assert_eq!(
+        origin.distance_to(&antipode),
+        origin.distance_to_with(&antipode, DistanceModel::Vincenty)
+    );
+}
+
+#[test]
+fn test_approx_eq() {
+    // Half a minute of latitude is half a nautical mile
+    let a = coordinate(20.0, -65.0);
+    let b = coordinate(20.0 + 0.5 / 60.0, -65.0);
+    assert_ne!(a, b);
+    assert!(a.approx_eq(&b, 1.0));
+    assert!(!a.approx_eq(&b, 0.25));
+
+    assert!(a.latitude.approx_eq(&b.latitude, 0.01));
+    assert!(!a.latitude.approx_eq(&b.latitude, 0.005));
+
+    let east = Longitude::from_signed_degrees(179.9);
+    let west = Longitude::from_signed_degrees(-179.9);
+    assert!(east.approx_eq(&west, 0.25));
+    assert!(!east.approx_eq(&west, 0.1));
+}
+
+#[test]
+fn test_bearing_to() {
+    let origin = coordinate(10.0, -60.0);
+    let degrees = |to: Coordinate| origin.bearing_to(&to).angle().decimal_degrees();
+    assert_eq!(0.0, degrees(coordinate(11.0, -60.0)));
+    assert_eq!(180.0, degrees(coordinate(9.0, -60.0)));
+    assert!((degrees(coordinate(10.0, -59.0)) - 90.0).abs() < 0.1);
+    assert!((degrees(coordinate(10.0, -61.0)) - 270.0).abs() < 0.1);
+
+    // Eastward across the antimeridian
+    let west = coordinate(10.0, 179.5).bearing_to(&coordinate(10.0, -179.5));
+    assert!((west.angle().decimal_degrees() - 90.0).abs() < 0.1);
+}
+
+#[test]
+fn test_antimeridian() {
+    let east = coordinate(10.0, 179.0);
+    let west = coordinate(10.0, -179.0);
+
+    let midpoint = east.midpoint(&west);
+    assert_eq!(180.0, midpoint.longitude.signed_degrees_east());
+    assert_eq!(LongitudeHemisphere::EAST, midpoint.longitude.hemisphere);
+    let quarter = east.interpolate(&west, 0.25);
+    assert_eq!(179.5, quarter.longitude.signed_degrees_east());
+    assert!(quarter.approx_eq(&coordinate(10.0, -180.5), 0.01));
+
+    let wrapped = Longitude {
+        angle: Angle::with_degrees_minutes_seconds(190, 0, 0),
+        hemisphere: LongitudeHemisphere::EAST,
+    };
+    assert_eq!(-170.0, wrapped.normalized().signed_degrees_east());
+
+    let bounds = bounding_box(&[east, west, coordinate(11.0, 179.5)]).unwrap();
+    assert!(bounds.crosses_antimeridian());
+    assert_eq!(179.0, bounds.west.signed_degrees_east());
+    assert_eq!(-179.0, bounds.east.signed_degrees_east());
+    assert_eq!(11.0, bounds.north.signed_degrees_north());
+
+    let bounds = bounding_box(&[coordinate(20.0, -65.0), coordinate(18.0, -61.0)]).unwrap();
+    assert!(!bounds.crosses_antimeridian());
+    assert_eq!(-65.0, bounds.west.signed_degrees_east());
+    assert_eq!(-61.0, bounds.east.signed_degrees_east());
+
+    assert_eq!(None, bounding_box(&[]));
+}
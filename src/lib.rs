@@ -1,11 +1,195 @@
+//! Parsing and analysis of tropical cyclone reconnaissance data.
+//!
+//! ```
+//! use libcyclone::geo::{Coordinate, Latitude, LatitudeHemisphere, Longitude, LongitudeHemisphere};
+//! use libcyclone::measure::Angle;
+//! use libcyclone::recon::HDOBMessage;
+//!
+//! let bulletin = "000
+//! URNT15 KNHC 051606
+//! AF309 0112E KAY                HDOB 12 20220905
+//! 155100 2601N 09348W 3926 07749 0432 -173 //// 222015 015 /// /// 05
+//! $$";
+//! let message = HDOBMessage::parse(bulletin).unwrap();
+//!
+//! let expected = Coordinate {
+//!     latitude: Latitude {
+//!         angle: Angle::with_degrees_minutes_seconds(26, 1, 0),
+//!         hemisphere: LatitudeHemisphere::NORTH,
+//!     },
+//!     longitude: Longitude {
+//!         angle: Angle::with_degrees_minutes_seconds(93, 48, 0),
+//!         hemisphere: LongitudeHemisphere::WEST,
+//!     },
+//! };
+//! assert_eq!("AF309 0112E KAY", message.mission_id);
+//! assert_eq!(expected, message.obs[0].location);
+//! ```
+
 #![allow(uncommon_codepoints)]
 
-mod geo;
-mod measure;
-mod recon;
+pub mod geo;
+pub mod measure;
+pub mod obs;
+pub mod recon;
+
+use crate::geo::Coordinate;
+use crate::measure::{Pressure, Speed};
+
+/// Commonly used types, for glob import.
+///
+/// Covers the HDOB message types, coordinates, the measurement types observations are built
+/// from, and [`Basin`]. Items are only ever added here, never removed or renamed.
+///
+/// ```
+/// use libcyclone::prelude::*;
+///
+/// let line = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00";
+/// let ob: HDHALog = line.parse().unwrap();
+/// assert_eq!(Some(Speed::with_knots(41)), ob.wind.map(|w: Wind| w.speed));
+/// ```
+pub mod prelude {
+    pub use crate::geo::{Coordinate, Latitude, Longitude};
+    pub use crate::measure::{Direction, Pressure, Speed, Temperature, Wind};
+    pub use crate::obs::MetObservation;
+    pub use crate::recon::{HDHALog, HDOBMessage};
+    pub use crate::Basin;
+}
 
+/// A tropical cyclone basin, as divided among the warning centers responsible for it.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Basin {
     NorthAtlantic,
     EastPacific,
     CentralPacific,
-}
\ No newline at end of file
+    WestPacific,
+    NorthIndian,
+    SouthwestIndian,
+    AustralianRegion,
+    SouthPacific,
+}
+
+impl Basin {
+    /// The basin containing `coordinate`, or `None` for the South Atlantic and southeast Pacific,
+    /// which have no warning center.
+    ///
+    /// The equator divides the hemispheres and itself counts as north, whether it is written
+    /// 00°00'N or 00°00'S. North of it, the boundaries are 40°E (North Indian),
+    /// 100°E (West Pacific), 180° (Central Pacific), 140°W (East Pacific), and the Central
+    /// American isthmus (North Atlantic), taken as 100°W north of 18°N, 80°W south of 8°N, and a
+    /// straight line between. South of it, they are 20°E (Southwest Indian), 90°E (Australian
+    /// region), 160°E (South Pacific), and 120°W.
+    pub fn from_coordinate(coordinate: &Coordinate) -> Option<Basin> {
+        let lat = coordinate.latitude.signed_degrees_north();
+        let lon = coordinate.longitude.normalized().signed_degrees_east();
+        if lat >= 0.0 {
+            let isthmus = -100.0 + 2.0 * (18.0 - lat.clamp(8.0, 18.0));
+            Some(match lon {
+                lon if (40.0..100.0).contains(&lon) => Self::NorthIndian,
+                lon if lon >= 100.0 => Self::WestPacific,
+                lon if lon < -140.0 => Self::CentralPacific,
+                lon if lon < isthmus => Self::EastPacific,
+                _ => Self::NorthAtlantic,
+            })
+        } else {
+            match lon {
+                lon if (20.0..90.0).contains(&lon) => Some(Self::SouthwestIndian),
+                lon if (90.0..160.0).contains(&lon) => Some(Self::AustralianRegion),
+                lon if (-120.0..20.0).contains(&lon) => None,
+                _ => Some(Self::SouthPacific),
+            }
+        }
+    }
+
+    /// Estimates the maximum sustained wind from a storm's minimum central pressure.
+    ///
+    /// The Atlantic uses Kraft's (1961) rule, `V = 14 √(1013 − P)`. The other basins use
+    /// Atkinson & Holliday's (1977) western Pacific fit, `V = 6.7 (1010 − P)^0.644`. `V` is in
+    /// knots and `P` in mb; pressures at or above the ambient value give calm. These are
+    /// climatological fits with errors of 10-20 kt for individual storms.
+    pub fn estimate_wind_from_pressure(&self, p: Pressure) -> Speed {
+        let mb = p.microbars() as f64 / 1000.0;
+        let knots = match self {
+            Self::NorthAtlantic => 14.0 * (1013.0 - mb).max(0.0).sqrt(),
+            _ => 6.7 * (1010.0 - mb).max(0.0).powf(0.644),
+        };
+        Speed::with_knots(knots.round() as u32)
+    }
+}
+
+#[test]
+fn test_estimate_wind_from_pressure() {
+    let atlantic =
+        Basin::NorthAtlantic.estimate_wind_from_pressure(Pressure::with_microbars(950_000));
+    assert!((80..=120).contains(&atlantic.knots()));
+
+    let pacific = Basin::EastPacific.estimate_wind_from_pressure(Pressure::with_microbars(950_000));
+    assert!((80..=120).contains(&pacific.knots()));
+    assert!(pacific < atlantic);
+
+    let calm =
+        Basin::NorthAtlantic.estimate_wind_from_pressure(Pressure::with_microbars(1_015_000));
+    assert_eq!(Speed::with_knots(0), calm);
+}
+
+#[test]
+fn test_basin_from_coordinate() {
+    use crate::geo::{Latitude, LatitudeHemisphere, Longitude, LongitudeHemisphere};
+    use crate::measure::Angle;
+
+    let at = |lat: f64, lon: f64| {
+        let coordinate = Coordinate {
+            latitude: Latitude {
+                angle: Angle::with_decimal_degrees(lat.abs()),
+                hemisphere: if lat < 0.0 {
+                    LatitudeHemisphere::SOUTH
+                } else {
+                    LatitudeHemisphere::NORTH
+                },
+            },
+            longitude: Longitude {
+                angle: Angle::with_decimal_degrees(lon.abs()),
+                hemisphere: if lon < 0.0 {
+                    LongitudeHemisphere::WEST
+                } else {
+                    LongitudeHemisphere::EAST
+                },
+            },
+        };
+        Basin::from_coordinate(&coordinate)
+    };
+
+    assert_eq!(Some(Basin::NorthAtlantic), at(20.1, -61.7));
+    assert_eq!(Some(Basin::NorthAtlantic), at(15.0, -85.0));
+    assert_eq!(Some(Basin::EastPacific), at(15.0, -95.0));
+    assert_eq!(Some(Basin::CentralPacific), at(19.0, -155.0));
+    assert_eq!(Some(Basin::WestPacific), at(15.0, 135.0));
+    assert_eq!(Some(Basin::NorthIndian), at(15.0, 88.0));
+    assert_eq!(Some(Basin::SouthwestIndian), at(-18.0, 55.0));
+    assert_eq!(Some(Basin::AustralianRegion), at(-15.0, 125.0));
+    assert_eq!(Some(Basin::SouthPacific), at(-17.0, 178.0));
+    assert_eq!(Some(Basin::SouthPacific), at(-17.0, -150.0));
+    assert_eq!(None, at(-25.0, -40.0));
+
+    // The equator and the 90°E/160°E lines
+    assert_eq!(Some(Basin::NorthIndian), at(0.0, 89.0));
+    let equator_south = Coordinate {
+        latitude: Latitude {
+            angle: Angle::with_degrees_minutes_seconds(0, 0, 0),
+            hemisphere: LatitudeHemisphere::SOUTH,
+        },
+        longitude: Longitude {
+            angle: Angle::with_decimal_degrees(89.0),
+            hemisphere: LongitudeHemisphere::EAST,
+        },
+    };
+    assert_eq!(
+        Some(Basin::NorthIndian),
+        Basin::from_coordinate(&equator_south)
+    );
+    assert_eq!(Some(Basin::SouthwestIndian), at(-1.0 / 60.0, 89.0));
+    assert_eq!(Some(Basin::NorthAtlantic), at(0.0, -30.0));
+    assert_eq!(None, at(-1.0 / 60.0, -30.0));
+    assert_eq!(Some(Basin::AustralianRegion), at(-0.5, 90.0));
+    assert_eq!(Some(Basin::SouthPacific), at(-10.0, 160.0));
+}
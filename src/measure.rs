@@ -1,6 +1,9 @@
 #![allow(dead_code)]
 
-use std::fmt::{Debug, Formatter};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
 
 /// Barometric pressure
 /// (stored in microbars)
@@ -8,10 +11,18 @@ use std::fmt::{Debug, Formatter};
 pub struct Pressure(i32);
 
 impl Pressure {
-    pub fn with_microbars(µb: i32) -> Self {
+    pub const fn with_microbars(µb: i32) -> Self {
         Self(µb)
     }
 
+    pub fn with_millibars_f64(mb: f64) -> Self {
+        Self((mb * 1000.0).round() as i32)
+    }
+
+    pub fn with_inches_hg(in_hg: f64) -> Self {
+        Self((in_hg * 33_863.9).round() as i32)
+    }
+
     pub fn microbars(&self) -> i32 {
         self.0
     }
@@ -19,6 +30,53 @@ impl Pressure {
     pub fn millibars(&self) -> i32 {
         self.0 / 1000
     }
+
+    pub fn approx_eq(&self, other: &Self, tolerance: Self) -> bool {
+        self.0.abs_diff(other.0) <= tolerance.0.unsigned_abs()
+    }
+
+    /// Height of this pressure surface in the U.S. Standard Atmosphere, in meters.
+    /// Only the troposphere and lower stratosphere (up to 20 km, ~54.7 mb) are modeled.
+    pub fn standard_height(&self) -> Option<f64> {
+        let mb = self.0 as f64 / 1000.0;
+        if mb > 226.321 {
+            // Troposphere: constant lapse rate of 6.5 K/km
+            Some(44_330.8 * (1.0 - (mb / 1013.25).powf(0.190_263)))
+        } else if mb > 54.7489 {
+            // Lower stratosphere: isothermal at 216.65 K
+            Some(11_000.0 + 6_341.62 * (226.321 / mb).ln())
+        } else {
+            None
+        }
+    }
+}
+
+#[test]
+fn test_pressure_approx_eq() {
+    let standard = Pressure::with_inches_hg(29.92);
+    let metric = Pressure::with_millibars_f64(1013.2);
+    assert_ne!(standard, metric);
+    assert!(standard.approx_eq(&metric, Pressure::with_microbars(100)));
+    assert!(!standard.approx_eq(
+        &Pressure::with_millibars_f64(1012.0),
+        Pressure::with_microbars(100)
+    ));
+}
+
+#[test]
+fn test_standard_height() {
+    let sea_level = Pressure::with_microbars(1_013_250)
+        .standard_height()
+        .unwrap();
+    assert!(sea_level.abs() < 1.0);
+
+    let mid = Pressure::with_microbars(500_000).standard_height().unwrap();
+    assert!((mid - 5_574.0).abs() < 5.0);
+
+    let tropopause = Pressure::with_microbars(226_321).standard_height().unwrap();
+    assert!((tropopause - 11_000.0).abs() < 1.0);
+
+    assert_eq!(None, Pressure::with_microbars(50_000).standard_height());
 }
 
 impl Debug for Pressure {
@@ -27,6 +85,47 @@ impl Debug for Pressure {
     }
 }
 
+/// A sea-level or surface pressure known to be plausible for a tropical cyclone environment,
+/// between [`SurfacePressure::MIN`] (850 mb, below the lowest ever observed, 870 mb) and
+/// [`SurfacePressure::MAX`] (1050 mb).
+///
+/// Anything outside that range is a corrupt group or a bad extrapolation, so code looking for
+/// minimum pressures can trust values of this type.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub struct SurfacePressure(Pressure);
+
+impl SurfacePressure {
+    pub const MIN: Pressure = Pressure::with_microbars(850_000);
+    pub const MAX: Pressure = Pressure::with_microbars(1_050_000);
+
+    /// Returns `None` if `pressure` is outside the plausible range.
+    pub fn new(pressure: Pressure) -> Option<Self> {
+        (Self::MIN..=Self::MAX)
+            .contains(&pressure)
+            .then_some(Self(pressure))
+    }
+
+    pub fn pressure(&self) -> Pressure {
+        self.0
+    }
+}
+
+#[test]
+fn test_surface_pressure() {
+    let p = Pressure::with_microbars(1_011_400);
+    assert_eq!(Some(p), SurfacePressure::new(p).map(|sp| sp.pressure()));
+    assert!(SurfacePressure::new(SurfacePressure::MIN).is_some());
+    assert!(SurfacePressure::new(SurfacePressure::MAX).is_some());
+    assert_eq!(
+        None,
+        SurfacePressure::new(Pressure::with_microbars(600_000))
+    );
+    assert_eq!(
+        None,
+        SurfacePressure::new(Pressure::with_microbars(1_050_100))
+    );
+}
+
 /// D-Value: the difference between the height of a particular pressure surface above sea level
 /// and the height of that same isobaric surface from the U.S. Standard Atmosphere.
 /// (stored in Meters)
@@ -40,6 +139,70 @@ impl DValue {
     pub fn meters(&self) -> i32 {
         self.0
     }
+
+    pub fn meters_f64(&self) -> f64 {
+        self.0 as f64
+    }
+
+    /// Whether the pressure surface lies below its standard-atmosphere height, as it does in
+    /// low pressure.
+    pub fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn approx_eq(&self, other: &Self, tolerance: Self) -> bool {
+        self.0.abs_diff(other.0) <= tolerance.0.unsigned_abs()
+    }
+}
+
+impl Neg for DValue {
+    type Output = DValue;
+
+    fn neg(self) -> DValue {
+        Self(-self.0)
+    }
+}
+
+/// Offsets a height by a D-value, stopping at sea level, which [`Altitude`] cannot go below.
+/// Use [`Altitude::checked_add_dvalue`] to detect that case instead.
+impl Add<DValue> for Altitude {
+    type Output = Altitude;
+
+    fn add(self, d: DValue) -> Altitude {
+        Self(self.0.saturating_add_signed(d.0))
+    }
+}
+
+/// Offsets a height by the negation of a D-value, stopping at sea level like
+/// `Altitude + DValue`. Use [`Altitude::checked_sub_dvalue`] to detect that case instead.
+impl Sub<DValue> for Altitude {
+    type Output = Altitude;
+
+    fn sub(self, d: DValue) -> Altitude {
+        let m = self.0 as i64 - d.0 as i64;
+        Self(m.clamp(0, u32::MAX as i64) as u32)
+    }
+}
+
+#[test]
+fn test_dvalue_ops() {
+    let d = -DValue::with_meters(30);
+    assert_eq!(DValue::with_meters(-30), d);
+    assert_eq!(-30.0, d.meters_f64());
+    assert_eq!(Altitude::with_meters(2970), Altitude::with_meters(3000) + d);
+    assert_eq!(Altitude::with_meters(3030), Altitude::with_meters(3000) - d);
+
+    // A large negative D-value low down would put the result below sea level
+    let low = Altitude::with_meters(20);
+    assert_eq!(None, low.checked_add_dvalue(d));
+    assert_eq!(None, low.checked_sub_dvalue(-d));
+    assert_eq!(Some(Altitude::with_meters(50)), low.checked_sub_dvalue(d));
+    assert_eq!(Altitude::with_meters(0), low + d);
+    assert_eq!(Altitude::with_meters(0), low - -d);
+    assert_eq!(
+        Altitude::with_meters(0),
+        low - DValue::with_meters(i32::MAX)
+    );
 }
 
 impl Debug for DValue {
@@ -54,13 +217,58 @@ impl Debug for DValue {
 pub struct Angle(u32);
 
 impl Angle {
+    /// The parts are not range-checked, so `(0, 90, 0)` is 1°30'; use
+    /// [`Angle::try_with_degrees_minutes_seconds`] for untrusted values.
     pub fn with_degrees_minutes_seconds(d: u32, m: u32, s: u32) -> Self {
         Self(d * 60 * 60 + m * 60 + s)
     }
 
+    /// Returns `None` unless minutes and seconds are below 60 and the angle is at most 360°.
+    /// Callers with a narrower range, such as latitudes, must check it themselves.
+    pub fn try_with_degrees_minutes_seconds(d: u32, m: u32, s: u32) -> Option<Self> {
+        (m < 60 && s < 60 && (d < 360 || (d, m, s) == (360, 0, 0)))
+            .then(|| Self(d * 60 * 60 + m * 60 + s))
+    }
+
+    /// Rounds to the nearest arcsecond.
+    pub fn with_decimal_degrees(degrees: f64) -> Self {
+        Self((degrees * 3600.0).round() as u32)
+    }
+
+    pub fn decimal_degrees(&self) -> f64 {
+        self.0 as f64 / 3600.0
+    }
+
     pub fn degrees_minutes_seconds(&self) -> (u32, u32, u32) {
         (self.0 / (60 * 60), (self.0 % (60 * 60) / 60), (self.0 % 60))
     }
+
+    pub fn approx_eq(&self, other: &Self, tolerance: Self) -> bool {
+        self.0.abs_diff(other.0) <= tolerance.0
+    }
+}
+
+#[test]
+fn test_angle_checked() {
+    assert_eq!(
+        Some(Angle::with_decimal_degrees(20.1)),
+        Angle::try_with_degrees_minutes_seconds(20, 6, 0)
+    );
+    assert_eq!(None, Angle::try_with_degrees_minutes_seconds(20, 75, 0));
+    assert_eq!(None, Angle::try_with_degrees_minutes_seconds(20, 6, 60));
+    assert!(Angle::try_with_degrees_minutes_seconds(360, 0, 0).is_some());
+    assert_eq!(None, Angle::try_with_degrees_minutes_seconds(360, 59, 59));
+    assert_eq!(None, Angle::try_with_degrees_minutes_seconds(360, 0, 1));
+    // The unchecked constructor carries out-of-range parts over, as it always has
+    assert_eq!(
+        Angle::with_decimal_degrees(1.5),
+        Angle::with_degrees_minutes_seconds(0, 90, 0)
+    );
+    // Would wrap around u32 unchecked
+    assert_eq!(
+        None,
+        Angle::try_with_degrees_minutes_seconds(2_000_000, 0, 0)
+    );
 }
 
 impl Debug for Angle {
@@ -82,6 +290,20 @@ impl Altitude {
     pub fn meters(&self) -> u32 {
         self.0
     }
+
+    pub fn approx_eq(&self, other: &Self, tolerance: Self) -> bool {
+        self.0.abs_diff(other.0) <= tolerance.0
+    }
+
+    /// This height offset by `d`, or `None` if that would be below sea level.
+    pub fn checked_add_dvalue(&self, d: DValue) -> Option<Altitude> {
+        self.0.checked_add_signed(d.0).map(Self)
+    }
+
+    /// This height offset by the negation of `d`, or `None` if that would be below sea level.
+    pub fn checked_sub_dvalue(&self, d: DValue) -> Option<Altitude> {
+        u32::try_from(self.0 as i64 - d.0 as i64).ok().map(Self)
+    }
 }
 
 impl Debug for Altitude {
@@ -100,12 +322,26 @@ impl Temperature {
         Self(mk)
     }
 
+    pub fn millikelvin(&self) -> u32 {
+        self.0
+    }
+
+    /// Like [`Temperature::with_millikelvin`], but takes a signed value and returns `None` when
+    /// it is below absolute zero or too large to store.
+    pub fn try_with_millikelvin(mk: i64) -> Option<Self> {
+        u32::try_from(mk).ok().map(Self)
+    }
+
+    /// Panics below absolute zero; use [`Temperature::try_with_millicelsius`] for untrusted
+    /// values.
     pub fn with_millicelsius(mc: i32) -> Self {
-        let mk = mc + 273150;
-        if mk < 0 {
-            panic!("Temperature less than absolute zero: {} mK", mk)
-        }
-        Self(mk as u32)
+        Self::try_with_millicelsius(mc)
+            .unwrap_or_else(|| panic!("Temperature less than absolute zero: {} m°C", mc))
+    }
+
+    /// Returns `None` below absolute zero.
+    pub fn try_with_millicelsius(mc: i32) -> Option<Self> {
+        Self::try_with_millikelvin(mc as i64 + 273150)
     }
 
     pub fn celsius(&self) -> u32 {
@@ -115,6 +351,25 @@ impl Temperature {
     pub fn kelvin(&self) -> u32 {
         self.0 / 1000
     }
+
+    pub fn approx_eq(&self, other: &Self, tolerance: Self) -> bool {
+        self.0.abs_diff(other.0) <= tolerance.0
+    }
+}
+
+#[test]
+fn test_temperature_checked() {
+    assert_eq!(None, Temperature::try_with_millicelsius(-300_000));
+    assert_eq!(
+        Some(Temperature::with_millikelvin(0)),
+        Temperature::try_with_millicelsius(-273_150)
+    );
+    assert_eq!(
+        Some(Temperature::with_millicelsius(20_100)),
+        Temperature::try_with_millicelsius(20_100)
+    );
+    assert_eq!(None, Temperature::try_with_millikelvin(-1));
+    assert_eq!(None, Temperature::try_with_millikelvin(u32::MAX as i64 + 1));
 }
 
 impl Debug for Temperature {
@@ -124,22 +379,132 @@ impl Debug for Temperature {
 }
 
 /// Speed
-/// (stored in knots)
+/// (stored in hundredths of a knot)
+///
+/// HDOB reports whole knots, but derived and externally processed speeds can be finer, and
+/// truncating them to whole knots would skew conversions to other units.
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub struct Speed(u32);
 
+const MPH_PER_KNOT: f64 = 1.150_779;
+const KPH_PER_KNOT: f64 = 1.852;
+
 impl Speed {
+    /// Saturates at the largest representable speed, about 42.9 million kt; see
+    /// [`Speed::try_with_knots`].
     pub fn with_knots(kt: u32) -> Self {
-        Self(kt)
+        Self(kt.saturating_mul(100))
+    }
+
+    /// Like [`Speed::with_knots`], but returns `None` when the speed is too large to represent.
+    pub fn try_with_knots(kt: u32) -> Option<Self> {
+        kt.checked_mul(100).map(Self)
+    }
+
+    /// Rounds to the nearest hundredth of a knot; negative speeds become calm, and speeds too
+    /// large to represent saturate.
+    pub fn with_knots_f64(kt: f64) -> Self {
+        Self((kt * 100.0).round().max(0.0) as u32)
     }
+
+    /// The speed rounded to the nearest whole knot.
     pub fn knots(&self) -> u32 {
-        self.0
+        ((self.0 as u64 + 50) / 100) as u32
+    }
+
+    pub fn knots_f64(&self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    /// Statute miles per hour.
+    pub fn mph(&self) -> f64 {
+        self.knots_f64() * MPH_PER_KNOT
+    }
+
+    /// Kilometers per hour.
+    pub fn kph(&self) -> f64 {
+        self.knots_f64() * KPH_PER_KNOT
+    }
+
+    pub fn approx_eq(&self, other: &Self, tolerance: Self) -> bool {
+        self.0.abs_diff(other.0) <= tolerance.0
+    }
+
+    /// The map color for this wind speed, from [`WIND_COLOR_SCALE`].
+    pub fn wind_color(&self) -> (u8, u8, u8) {
+        WIND_COLOR_SCALE
+            .iter()
+            .rev()
+            .find(|(min_knots, _)| self.0 >= min_knots * 100)
+            .map(|(_, color)| *color)
+            .unwrap()
+    }
+
+    /// The Beaufort force, 0 (calm, under 1 kt) to 12 (hurricane force, 64 kt and above), of
+    /// the speed rounded to the nearest knot.
+    pub fn beaufort(&self) -> u8 {
+        // The lowest speed, in knots, of forces 1 through 12
+        const THRESHOLDS: [u32; 12] = [1, 4, 7, 11, 17, 22, 28, 34, 41, 48, 56, 64];
+        THRESHOLDS.partition_point(|min| self.knots() >= *min) as u8
     }
 }
 
+/// The colors conventionally used to map tropical cyclone intensity, as `(minimum knots, RGB)`
+/// in increasing order: tropical depression, tropical storm, then Saffir-Simpson categories 1
+/// through 5.
+pub const WIND_COLOR_SCALE: [(u32, (u8, u8, u8)); 7] = [
+    (0, (0x5e, 0xba, 0xff)),
+    (34, (0x00, 0xfa, 0xf4)),
+    (64, (0xff, 0xff, 0xcc)),
+    (83, (0xff, 0xe7, 0x75)),
+    (96, (0xff, 0xc1, 0x40)),
+    (113, (0xff, 0x8f, 0x20)),
+    (137, (0xff, 0x60, 0x60)),
+];
+
+#[test]
+fn test_wind_color() {
+    assert_eq!((0xff, 0x60, 0x60), Speed::with_knots(150).wind_color());
+    assert_eq!((0xff, 0x60, 0x60), Speed::with_knots(137).wind_color());
+    assert_eq!((0xff, 0x8f, 0x20), Speed::with_knots(136).wind_color());
+    assert_eq!((0x5e, 0xba, 0xff), Speed::with_knots(0).wind_color());
+}
+
+#[test]
+fn test_beaufort() {
+    let force = |kt| Speed::with_knots(kt).beaufort();
+    assert_eq!(0, force(0));
+    assert_eq!(1, force(1));
+    assert_eq!(7, force(33));
+    assert_eq!(8, force(34));
+    assert_eq!(11, force(63));
+    assert_eq!(12, force(64));
+    assert_eq!(12, force(150));
+    assert_eq!(8, Speed::with_knots_f64(33.6).beaufort());
+}
+
+#[test]
+fn test_speed_precision() {
+    let speed = Speed::with_knots_f64(41.6);
+    assert_eq!(42, speed.knots());
+    assert_eq!(41.6, speed.knots_f64());
+    assert_eq!(47.9, (speed.mph() * 10.0).round() / 10.0);
+    assert!((speed.kph() - 77.04).abs() < 0.01);
+    assert_eq!(Speed::with_knots(41), Speed::with_knots_f64(41.0));
+    assert_eq!("Speed(41.6 kt)", format!("{:?}", speed));
+    assert_eq!("Speed(41 kt)", format!("{:?}", Speed::with_knots(41)));
+
+    // The largest speeds neither wrap nor overflow when rounded
+    let max = Speed::from_raw_i64(u32::MAX as i64).unwrap();
+    assert_eq!(42_949_673, max.knots());
+    assert_eq!(max, Speed::with_knots(u32::MAX));
+    assert_eq!(None, Speed::try_with_knots(u32::MAX));
+    assert_eq!(Some(Speed::with_knots(41)), Speed::try_with_knots(41));
+}
+
 impl Debug for Speed {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Speed({} kt)", self.0)
+        write!(f, "Speed({} kt)", self.knots_f64())
     }
 }
 
@@ -149,12 +514,26 @@ impl Debug for Speed {
 pub struct RainRate(u32);
 
 impl RainRate {
+    /// The highest rain rate taken as plausible by default. SFMR retrievals rarely exceed
+    /// 100 mm/hr even in eyewalls, so values near the top of the three-digit group are almost
+    /// certainly corrupt.
+    pub const MAX_PLAUSIBLE: RainRate = RainRate(300);
+
     pub fn with_mm_per_hr(mm_p_hr: u32) -> Self {
         Self(mm_p_hr)
     }
     pub fn mm_per_hr(&self) -> u32 {
         self.0
     }
+
+    pub fn approx_eq(&self, other: &Self, tolerance: Self) -> bool {
+        self.0.abs_diff(other.0) <= tolerance.0
+    }
+
+    /// Whether the rate is at most [`RainRate::MAX_PLAUSIBLE`].
+    pub fn is_plausible(&self) -> bool {
+        *self <= Self::MAX_PLAUSIBLE
+    }
 }
 
 impl Debug for RainRate {
@@ -170,6 +549,35 @@ impl Direction {
     pub fn with_angle(angle: Angle) -> Direction {
         Self(angle)
     }
+
+    /// Direction in degrees clockwise from north, wrapped into [0, 360).
+    pub fn from_degrees(degrees: f64) -> Direction {
+        let seconds = (degrees * 3600.0).round().rem_euclid(360.0 * 3600.0);
+        Self(Angle(seconds as u32))
+    }
+
+    pub fn angle(&self) -> Angle {
+        self.0
+    }
+
+    /// This direction in the mathematical convention: the direction of travel, in radians
+    /// counterclockwise from east, wrapped into [0, 2π).
+    ///
+    /// A meteorological direction is where the wind blows *from*, clockwise from north. Flipping
+    /// it 180° gives the direction of travel, and measuring that from east the other way round is
+    /// `90° − travel`, so altogether `math = 270° − met`. A westerly (270°) is therefore 0 rad,
+    /// blowing toward +x, and a northerly (360°) is 3π/2, blowing toward −y.
+    pub fn to_math_radians(&self) -> f64 {
+        (270.0 - self.0.decimal_degrees())
+            .rem_euclid(360.0)
+            .to_radians()
+    }
+
+    /// The meteorological direction of a vector pointing `radians` counterclockwise from east;
+    /// the inverse of [`Direction::to_math_radians`].
+    pub fn from_math_radians(radians: f64) -> Direction {
+        Self::from_degrees(270.0 - radians.to_degrees())
+    }
 }
 
 pub const NORTH: Direction = Direction(Angle(0));
@@ -187,4 +595,591 @@ impl Wind {
     pub fn with_direction_and_speed(direction: Direction, speed: Speed) -> Self {
         Self { direction, speed }
     }
+
+    /// Wind blowing from `degrees` clockwise from north at `knots`, as reported.
+    pub fn from_degrees_knots(degrees: f64, knots: u32) -> Self {
+        Self::with_direction_and_speed(Direction::from_degrees(degrees), Speed::with_knots(knots))
+    }
+}
+
+#[test]
+fn test_wind_from_degrees_knots() {
+    let expected = Wind::with_direction_and_speed(WEST, Speed::with_knots(41));
+    assert_eq!(expected, Wind::from_degrees_knots(270.0, 41));
+
+    assert_eq!(NORTH, Direction::from_degrees(360.0));
+    assert_eq!(WEST, Direction::from_degrees(-90.0));
+}
+
+#[test]
+fn test_direction_math_radians() {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    // A westerly blows toward the east, along +x
+    assert_eq!(0.0, WEST.to_math_radians());
+    assert!((SOUTH.to_math_radians() - FRAC_PI_2).abs() < 1e-12);
+    assert!((EAST.to_math_radians() - PI).abs() < 1e-12);
+    assert!((NORTH.to_math_radians() - 3.0 * FRAC_PI_2).abs() < 1e-12);
+
+    assert_eq!(WEST, Direction::from_math_radians(0.0));
+    assert_eq!(NORTH, Direction::from_math_radians(-FRAC_PI_2));
+    let direction = Direction::from_degrees(123.0);
+    assert_eq!(
+        direction,
+        Direction::from_math_radians(direction.to_math_radians())
+    );
+}
+
+/// A wind measured at the aircraft's flight level, kept distinct from [`SurfaceWind`] so the
+/// two can't be compared or mixed up without an explicit reduction:
+///
+/// ```compile_fail
+/// use libcyclone::measure::{FlightLevelWind, Speed, SurfaceWind, Wind};
+///
+/// let flight_level = FlightLevelWind(Wind::from_degrees_knots(123.0, 41));
+/// let surface = SurfaceWind(Speed::with_knots(21));
+/// assert!(flight_level.0.speed > surface);
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct FlightLevelWind(pub Wind);
+
+impl FlightLevelWind {
+    /// Estimates the surface wind speed with the flight-level to surface reduction factors of
+    /// Franklin et al. (2003): 75% from 925 mb and below, 80% from 850 mb, and 90% from 700
+    /// mb, each applied to the nearest of those levels. Returns `None` above 600 mb, where no
+    /// standard factor applies.
+    pub fn reduce_to_surface(&self, flight_level: Pressure) -> Option<SurfaceWind> {
+        let factor = match flight_level.microbars() {
+            p if p >= 887_500 => 0.75,
+            p if p >= 775_000 => 0.80,
+            p if p >= 600_000 => 0.90,
+            _ => return None,
+        };
+        Some(SurfaceWind(Speed::with_knots_f64(
+            self.0.speed.knots_f64() * factor,
+        )))
+    }
+}
+
+/// A surface wind speed, as measured by SFMR or estimated from a [`FlightLevelWind`].
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub struct SurfaceWind(pub Speed);
+
+#[test]
+fn test_flight_level_wind_reduction() {
+    let wind = FlightLevelWind(Wind::from_degrees_knots(123.0, 100));
+    let at = |mb| {
+        wind.reduce_to_surface(Pressure::with_microbars(mb))
+            .map(|surface| surface.0)
+    };
+    assert_eq!(Some(Speed::with_knots(75)), at(923_600));
+    assert_eq!(Some(Speed::with_knots(80)), at(843_000));
+    assert_eq!(Some(Speed::with_knots(90)), at(697_000));
+    assert_eq!(None, at(392_600));
+}
+
+/// A measurement expressed in a single canonical unit, so formatting and export code can handle
+/// any measure uniformly.
+pub trait Quantity {
+    /// The measurement in [`Quantity::unit`]s.
+    fn value(&self) -> f64;
+
+    /// The label of the canonical unit, e.g. `"mb"`.
+    fn unit(&self) -> &'static str;
+}
+
+impl Quantity for Pressure {
+    fn value(&self) -> f64 {
+        self.0 as f64 / 1000.0
+    }
+
+    fn unit(&self) -> &'static str {
+        "mb"
+    }
+}
+
+impl Quantity for DValue {
+    fn value(&self) -> f64 {
+        self.0 as f64
+    }
+
+    fn unit(&self) -> &'static str {
+        "m"
+    }
+}
+
+impl Quantity for Angle {
+    fn value(&self) -> f64 {
+        self.decimal_degrees()
+    }
+
+    fn unit(&self) -> &'static str {
+        "deg"
+    }
+}
+
+impl Quantity for Altitude {
+    fn value(&self) -> f64 {
+        self.0 as f64
+    }
+
+    fn unit(&self) -> &'static str {
+        "m"
+    }
+}
+
+impl Quantity for Temperature {
+    fn value(&self) -> f64 {
+        self.0 as f64 / 1000.0
+    }
+
+    fn unit(&self) -> &'static str {
+        "K"
+    }
+}
+
+impl Quantity for Speed {
+    fn value(&self) -> f64 {
+        self.knots_f64()
+    }
+
+    fn unit(&self) -> &'static str {
+        "kt"
+    }
+}
+
+impl Quantity for RainRate {
+    fn value(&self) -> f64 {
+        self.0 as f64
+    }
+
+    fn unit(&self) -> &'static str {
+        "mm/hr"
+    }
+}
+
+impl Quantity for Direction {
+    fn value(&self) -> f64 {
+        self.0.value()
+    }
+
+    fn unit(&self) -> &'static str {
+        "deg"
+    }
+}
+
+#[test]
+fn test_quantity() {
+    let quantities: Vec<(Box<dyn Quantity>, f64, &str)> = vec![
+        (Box::new(Pressure::with_microbars(1_011_500)), 1011.5, "mb"),
+        (Box::new(DValue::with_meters(-12)), -12.0, "m"),
+        (
+            Box::new(Angle::with_degrees_minutes_seconds(20, 30, 0)),
+            20.5,
+            "deg",
+        ),
+        (Box::new(Altitude::with_meters(794)), 794.0, "m"),
+        (
+            Box::new(Temperature::with_millicelsius(20_100)),
+            293.25,
+            "K",
+        ),
+        (Box::new(Speed::with_knots(41)), 41.0, "kt"),
+        (Box::new(RainRate::with_mm_per_hr(2)), 2.0, "mm/hr"),
+        (Box::new(WEST), 270.0, "deg"),
+    ];
+    for (quantity, value, unit) in quantities {
+        assert_eq!(unit, quantity.unit());
+        assert!((quantity.value() - value).abs() < 1e-9, "{}", unit);
+    }
+}
+
+/// Access to the integer a measure is stored as, for compact persistence without unit
+/// conversion. The storage unit of each type is given in its documentation: microbars for
+/// [`Pressure`] and [`SurfacePressure`], meters for [`DValue`] and [`Altitude`], arcseconds for
+/// [`Angle`] and [`Direction`], millikelvin for [`Temperature`], hundredths of a knot for
+/// [`Speed`], and millimeters per hour for [`RainRate`].
+pub trait Raw: Sized {
+    /// The stored integer.
+    fn raw_i64(&self) -> i64;
+
+    /// Rebuilds a measure from [`Raw::raw_i64`], or `None` if `raw` is out of range for it.
+    fn from_raw_i64(raw: i64) -> Option<Self>;
+}
+
+impl Raw for Pressure {
+    fn raw_i64(&self) -> i64 {
+        self.0 as i64
+    }
+
+    fn from_raw_i64(raw: i64) -> Option<Self> {
+        i32::try_from(raw).ok().map(Self)
+    }
+}
+
+impl Raw for SurfacePressure {
+    fn raw_i64(&self) -> i64 {
+        self.0.raw_i64()
+    }
+
+    fn from_raw_i64(raw: i64) -> Option<Self> {
+        Pressure::from_raw_i64(raw).and_then(Self::new)
+    }
+}
+
+impl Raw for DValue {
+    fn raw_i64(&self) -> i64 {
+        self.0 as i64
+    }
+
+    fn from_raw_i64(raw: i64) -> Option<Self> {
+        i32::try_from(raw).ok().map(Self)
+    }
+}
+
+impl Raw for Angle {
+    fn raw_i64(&self) -> i64 {
+        self.0 as i64
+    }
+
+    fn from_raw_i64(raw: i64) -> Option<Self> {
+        u32::try_from(raw).ok().map(Self)
+    }
+}
+
+impl Raw for Altitude {
+    fn raw_i64(&self) -> i64 {
+        self.0 as i64
+    }
+
+    fn from_raw_i64(raw: i64) -> Option<Self> {
+        u32::try_from(raw).ok().map(Self)
+    }
+}
+
+impl Raw for Temperature {
+    fn raw_i64(&self) -> i64 {
+        self.0 as i64
+    }
+
+    fn from_raw_i64(raw: i64) -> Option<Self> {
+        Self::try_with_millikelvin(raw)
+    }
+}
+
+impl Raw for Speed {
+    fn raw_i64(&self) -> i64 {
+        self.0 as i64
+    }
+
+    fn from_raw_i64(raw: i64) -> Option<Self> {
+        u32::try_from(raw).ok().map(Self)
+    }
+}
+
+impl Raw for RainRate {
+    fn raw_i64(&self) -> i64 {
+        self.0 as i64
+    }
+
+    fn from_raw_i64(raw: i64) -> Option<Self> {
+        u32::try_from(raw).ok().map(Self)
+    }
+}
+
+/// Only values below a full circle (1,296,000 arcseconds) are valid directions.
+impl Raw for Direction {
+    fn raw_i64(&self) -> i64 {
+        self.0.raw_i64()
+    }
+
+    fn from_raw_i64(raw: i64) -> Option<Self> {
+        (0..360 * 60 * 60)
+            .contains(&raw)
+            .then_some(Self(Angle(raw as u32)))
+    }
+}
+
+#[test]
+fn test_raw() {
+    fn round_trip<T: Raw + Debug + PartialEq>(value: T, raw: i64) {
+        assert_eq!(raw, value.raw_i64());
+        assert_eq!(Some(value), T::from_raw_i64(raw));
+    }
+
+    round_trip(Pressure::with_microbars(1_011_400), 1_011_400);
+    round_trip(
+        SurfacePressure::new(Pressure::with_microbars(1_011_400)).unwrap(),
+        1_011_400,
+    );
+    round_trip(DValue::with_meters(-30), -30);
+    round_trip(Angle::with_degrees_minutes_seconds(20, 6, 0), 72_360);
+    round_trip(Altitude::with_meters(794), 794);
+    round_trip(Temperature::with_millicelsius(20_100), 293_250);
+    round_trip(Speed::with_knots_f64(41.6), 4160);
+    round_trip(RainRate::with_mm_per_hr(2), 2);
+    round_trip(WEST, 972_000);
+
+    assert_eq!(None, Pressure::from_raw_i64(i64::MAX));
+    assert_eq!(None, SurfacePressure::from_raw_i64(600_000));
+    assert_eq!(None, Temperature::from_raw_i64(-1));
+    assert_eq!(None, Direction::from_raw_i64(1_296_000));
+}
+
+// Unit-tagged values, for converting into and out of the measures with `From`/`Into`.
+//
+// The measures deliberately have no `From<u32>` or `From<f64>`: a bare number does not say
+// what unit it is in, so `let h: Altitude = 794.into()` would be an easy place to mix up feet
+// and meters. Wrapping the number in its unit first keeps that explicit. There is likewise no
+// `From<DValue> for Altitude`, since a D-value is a signed offset from the standard atmosphere
+// rather than a height; use `Altitude + DValue` instead.
+
+/// A height in feet. Converts to and from [`Altitude`], rounding to the nearest whole meter or
+/// foot.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Feet(pub u32);
+
+/// A height in meters, converting losslessly to and from [`Altitude`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Meters(pub u32);
+
+/// A speed in whole knots. Converts losslessly to [`Speed`], and back to the nearest knot.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Knots(pub u32);
+
+/// A pressure in millibars (hectopascals). Converts to [`Pressure`] to the nearest microbar.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Millibars(pub f64);
+
+const METERS_PER_FOOT: f64 = 0.3048;
+
+impl From<Feet> for Altitude {
+    fn from(feet: Feet) -> Self {
+        Self((feet.0 as f64 * METERS_PER_FOOT).round() as u32)
+    }
+}
+
+impl From<Altitude> for Feet {
+    fn from(altitude: Altitude) -> Self {
+        Self((altitude.0 as f64 / METERS_PER_FOOT).round() as u32)
+    }
+}
+
+impl From<Meters> for Altitude {
+    fn from(meters: Meters) -> Self {
+        Self(meters.0)
+    }
+}
+
+impl From<Altitude> for Meters {
+    fn from(altitude: Altitude) -> Self {
+        Self(altitude.0)
+    }
+}
+
+impl From<Knots> for Speed {
+    fn from(knots: Knots) -> Self {
+        Self::with_knots(knots.0)
+    }
+}
+
+impl From<Speed> for Knots {
+    fn from(speed: Speed) -> Self {
+        Self(speed.knots())
+    }
+}
+
+impl From<Millibars> for Pressure {
+    fn from(mb: Millibars) -> Self {
+        Self::with_millibars_f64(mb.0)
+    }
+}
+
+impl From<Angle> for Direction {
+    fn from(angle: Angle) -> Self {
+        Self::with_angle(angle)
+    }
+}
+
+#[test]
+fn test_unit_conversions() {
+    let altitude: Altitude = Feet(10_000).into();
+    assert_eq!(Altitude::with_meters(3048), altitude);
+    assert_eq!(Feet(10_000), altitude.into());
+    assert_eq!(Altitude::with_meters(794), Meters(794).into());
+    assert_eq!(Speed::with_knots(41), Knots(41).into());
+    assert_eq!(Knots(41), Speed::with_knots(41).into());
+    assert_eq!(
+        Pressure::with_microbars(1_011_400),
+        Millibars(1011.4).into()
+    );
+    assert_eq!(WEST, Angle::with_decimal_degrees(270.0).into());
+}
+
+/// Why text such as `"64kt"` could not be parsed as a measure.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MeasureParseError {
+    /// The text did not begin with a number.
+    InvalidNumber(String),
+    /// The unit was missing or is not one accepted for the quantity.
+    UnknownUnit(String),
+    /// The value cannot be represented, such as a negative speed or a temperature below
+    /// absolute zero.
+    OutOfRange(String),
+}
+
+impl Display for MeasureParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidNumber(text) => write!(f, "invalid number: {:?}", text),
+            Self::UnknownUnit(unit) => write!(f, "unknown unit: {:?}", unit),
+            Self::OutOfRange(text) => write!(f, "out of range: {:?}", text),
+        }
+    }
+}
+
+impl Error for MeasureParseError {}
+
+/// Splits text such as `"-40C"` or `"29.92 inHg"` into its number and its lowercased unit.
+fn split_unit(text: &str) -> Result<(f64, String), MeasureParseError> {
+    let text = text.trim();
+    let at = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '+' || c == '-'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(at);
+    let value: f64 = number
+        .parse()
+        .map_err(|_| MeasureParseError::InvalidNumber(text.to_string()))?;
+    Ok((value, unit.trim().to_ascii_lowercase()))
+}
+
+/// Parses a number followed by `kt` (also `kts`, `knots`), `mph`, `kph` (also `km/h`), or
+/// `m/s`, such as `"64kt"`.
+impl FromStr for Speed {
+    type Err = MeasureParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (value, unit) = split_unit(text)?;
+        let knots = match unit.as_str() {
+            "kt" | "kts" | "knots" => value,
+            "mph" => value / MPH_PER_KNOT,
+            "kph" | "km/h" => value / KPH_PER_KNOT,
+            "m/s" => value * 3600.0 / 1852.0,
+            _ => return Err(MeasureParseError::UnknownUnit(unit)),
+        };
+        let hundredths = (knots * 100.0).round();
+        if !(0.0..=u32::MAX as f64).contains(&hundredths) {
+            return Err(MeasureParseError::OutOfRange(text.to_string()));
+        }
+        Ok(Self(hundredths as u32))
+    }
+}
+
+/// Parses a number followed by `mb` (also `hPa`) or `inHg`, such as `"950mb"`.
+impl FromStr for Pressure {
+    type Err = MeasureParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (value, unit) = split_unit(text)?;
+        let microbars = match unit.as_str() {
+            "mb" | "hpa" => value * 1000.0,
+            "inhg" => value * 33_863.9,
+            _ => return Err(MeasureParseError::UnknownUnit(unit)),
+        }
+        .round();
+        if !(0.0..=i32::MAX as f64).contains(&microbars) {
+            return Err(MeasureParseError::OutOfRange(text.to_string()));
+        }
+        Ok(Self(microbars as i32))
+    }
+}
+
+/// Parses a number followed by `C`, `F`, or `K`, optionally with a degree sign, such as
+/// `"-40C"`.
+impl FromStr for Temperature {
+    type Err = MeasureParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (value, unit) = split_unit(text)?;
+        let kelvin = match unit.trim_start_matches('°') {
+            "c" => value + 273.15,
+            "f" => (value - 32.0) * 5.0 / 9.0 + 273.15,
+            "k" => value,
+            _ => return Err(MeasureParseError::UnknownUnit(unit)),
+        };
+        Self::try_with_millikelvin((kelvin * 1000.0).round() as i64)
+            .ok_or_else(|| MeasureParseError::OutOfRange(text.to_string()))
+    }
+}
+
+#[test]
+fn test_parse_with_units() {
+    assert_eq!(Ok(Speed::with_knots(64)), "64kt".parse::<Speed>());
+    assert_eq!(Ok(Speed::with_knots(64)), "64 knots".parse::<Speed>());
+    assert!("74mph"
+        .parse::<Speed>()
+        .unwrap()
+        .approx_eq(&Speed::with_knots(64), Speed::with_knots_f64(0.5)));
+    assert_eq!(Ok(Speed::with_knots(35)), "64.82km/h".parse::<Speed>());
+    assert_eq!(
+        Err(MeasureParseError::OutOfRange("-5kt".to_string())),
+        "-5kt".parse::<Speed>()
+    );
+
+    assert_eq!(
+        Ok(Pressure::with_microbars(950_000)),
+        "950mb".parse::<Pressure>()
+    );
+    assert_eq!(
+        Ok(Pressure::with_microbars(1_013_200)),
+        "1013.2 hPa".parse::<Pressure>()
+    );
+    assert_eq!(
+        Ok(Pressure::with_inches_hg(29.92)),
+        "29.92inHg".parse::<Pressure>()
+    );
+
+    // Too large to represent, rather than saturating
+    assert_eq!(
+        Err(MeasureParseError::OutOfRange("99999999999kt".to_string())),
+        "99999999999kt".parse::<Speed>()
+    );
+    assert_eq!(
+        Err(MeasureParseError::OutOfRange("9999999mb".to_string())),
+        "9999999mb".parse::<Pressure>()
+    );
+    assert_eq!(
+        Err(MeasureParseError::OutOfRange("-1mb".to_string())),
+        "-1mb".parse::<Pressure>()
+    );
+
+    assert_eq!(
+        Ok(Temperature::with_millicelsius(-40_000)),
+        "-40C".parse::<Temperature>()
+    );
+    assert_eq!(
+        Ok(Temperature::with_millicelsius(-40_000)),
+        "-40°F".parse::<Temperature>()
+    );
+    assert_eq!(
+        Ok(Temperature::with_millikelvin(300_000)),
+        "300K".parse::<Temperature>()
+    );
+    assert_eq!(
+        Err(MeasureParseError::OutOfRange("-300C".to_string())),
+        "-300C".parse::<Temperature>()
+    );
+
+    assert_eq!(
+        Err(MeasureParseError::UnknownUnit("psi".to_string())),
+        "14.7psi".parse::<Pressure>()
+    );
+    assert_eq!(
+        Err(MeasureParseError::UnknownUnit(String::new())),
+        "950".parse::<Pressure>()
+    );
+    assert_eq!(
+        Err(MeasureParseError::InvalidNumber("kt".to_string())),
+        "kt".parse::<Speed>()
+    );
 }
@@ -0,0 +1,18 @@
+use crate::geo::Coordinate;
+use crate::measure::{Pressure, Temperature, Wind};
+use chrono::{DateTime, Utc};
+
+/// A weather observation at a point in space and time, independent of the platform that took
+/// it (reconnaissance aircraft, buoy, surface station, ...).
+pub trait MetObservation {
+    fn time(&self) -> DateTime<Utc>;
+
+    fn location(&self) -> Coordinate;
+
+    /// Sea-level pressure, whether measured directly or extrapolated from aloft.
+    fn surface_pressure(&self) -> Option<Pressure>;
+
+    fn wind(&self) -> Option<Wind>;
+
+    fn temperature(&self) -> Option<Temperature>;
+}
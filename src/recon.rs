@@ -5,170 +5,5076 @@ use crate::geo::{
     LongitudeHemisphere::{EAST, WEST},
 };
 use crate::measure::{
-    Altitude, Angle, DValue, Direction, Pressure, RainRate, Speed, Temperature, Wind,
+    Altitude, Angle, DValue, Direction, FlightLevelWind, Pressure, RainRate, Speed,
+    SurfacePressure, SurfaceWind, Temperature, Wind,
 };
+use crate::obs::MetObservation;
 
-use chrono::{Date, DateTime, TimeZone, Utc};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc};
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 const MISSING: &str = "///";
 
+lazy_static! {
+    /// A WMO abbreviated heading, e.g. `URNT15 KNHC 051606`.
+    static ref WMO_RE: Regex = Regex::new(r"^[A-Z]{4}[0-9]{2} [A-Z]{4} [0-9]{6}").unwrap();
+}
+
+/// Whether a group is a missing-value marker: all slashes, as HDOB specifies, or all dots, as
+/// some relays substitute.
+fn is_missing(group: &str) -> bool {
+    !group.is_empty() && (group.bytes().all(|b| b == b'/') || group.bytes().all(|b| b == b'.'))
+}
+
+/// How far, in meters, a transmitted D-value may stray from the one implied by the height and
+/// aircraft pressure before the observation is considered suspect.
+const DVALUE_TOLERANCE: i32 = 50;
+
+/// The spacing between consecutive HDOB observations. Each line summarizes 30 seconds of flight,
+/// so a wider spacing means data was lost.
+pub const NOMINAL_OBS_INTERVAL: Duration = Duration::seconds(30);
+
+/// The flight level above which (i.e. at lower aircraft pressures) the extrapolated surface
+/// pressure group carries a D-value instead, as extrapolation from that high is unreliable.
+pub const DVALUE_PRESSURE_THRESHOLD: Pressure = Pressure::with_microbars(550_000);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input was blank, or held only headers and no observations.
+    Empty,
+    /// A required line or column was absent, or held only a missing-data marker.
+    Missing(&'static str),
+    /// A line or column was present but could not be interpreted.
+    Invalid { field: &'static str, text: String },
+    /// The bulletin held a different number of observations than expected, usually because
+    /// the transmission was truncated.
+    ObsCountMismatch { expected: usize, found: usize },
+    /// Consecutive observations shared a timestamp, under [`DuplicateTimes::Error`].
+    DuplicateTime(DateTime<Utc>),
+    /// The bulletin could not be read from its source.
+    Io {
+        kind: io::ErrorKind,
+        message: String,
+    },
+}
+
+impl ParseError {
+    fn invalid(field: &'static str, text: &str) -> Self {
+        Self::Invalid {
+            field,
+            text: text.to_string(),
+        }
+    }
+}
+
+impl From<io::Error> for ParseError {
+    fn from(error: io::Error) -> Self {
+        Self::Io {
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "bulletin contains no observations"),
+            Self::Missing(field) => write!(f, "missing {}", field),
+            Self::Invalid { field, text } => write!(f, "invalid {}: {:?}", field, text),
+            Self::ObsCountMismatch { expected, found } => {
+                write!(f, "expected {} observations, found {}", expected, found)
+            }
+            Self::DuplicateTime(time) => write!(f, "duplicate observation time {}", time),
+            Self::Io { message, .. } => write!(f, "unable to read bulletin: {}", message),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[test]
+fn test_parse_error_display() {
+    let error: Box<dyn Error + Send + Sync> = Box::new(ParseError::invalid("lat", "20O6N"));
+    assert_eq!("invalid lat: \"20O6N\"", error.to_string());
+
+    let error: Box<dyn Error + Send + Sync> = Box::new(ParseError::Missing("quality"));
+    assert_eq!("missing quality", error.to_string());
+}
+
+/// A problem found by [`HDOBMessage::parse_lenient`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// The data line at 1-based `line` could not be parsed and was left out.
+    SkippedLine { line: usize, error: ParseError },
+    /// The observation at `time` has quality flags set.
+    Questionable {
+        time: DateTime<Utc>,
+        flags: QualityFlags,
+    },
+    /// The observation at `time` failed a sanity check.
+    Sanity {
+        time: DateTime<Utc>,
+        violation: SanityViolation,
+    },
+    /// Observations are missing between `from` and `to`.
+    Gap {
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+    /// The bulletin as a whole could not be parsed, so the message returned with this warning
+    /// is empty.
+    Unparsable(ParseError),
+}
+
+/// Options controlling how bulletins are parsed.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ParseOptions {
+    /// If set, the number of observations a bulletin must contain.
+    ///
+    /// HDOB bulletins carry no observation count of their own, but a complete bulletin normally
+    /// holds 20 (ten minutes of 30-second observations). Setting this catches transmissions that
+    /// were cut off partway through; the final bulletin of a mission may legitimately be short.
+    pub expected_obs_count: Option<usize>,
+    /// How temperature and dewpoint groups are scaled.
+    pub temperature_scale: TemperatureScale,
+    /// How quality-control digits outside the HDOB code table are handled.
+    pub quality_mode: QualityMode,
+    /// How consecutive observations with the same timestamp are handled.
+    pub duplicate_times: DuplicateTimes,
+}
+
+/// The unit of temperature and dewpoint groups.
+///
+/// Current HDOB sends a sign and three digits in tenths of a degree Celsius (`+201` is
+/// 20.1°C). Some legacy reconnaissance archives send whole degrees instead, usually as a sign
+/// and two digits (`+20`). An archive whose flight-level temperatures read as implausibly
+/// cold in tenths (near 0°C in the tropics at low levels) is likely whole degrees.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum TemperatureScale {
+    #[default]
+    Tenths,
+    Whole,
+}
+
+/// The handling of quality-control codes outside the HDOB code table, such as a `7` or `8` in
+/// the meteorological digit.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum QualityMode {
+    /// Reject the observation with [`ParseError::Invalid`].
+    #[default]
+    Strict,
+    /// Mark every flag governed by the unknown digit questionable, and report a `tracing`
+    /// warning when that feature is enabled.
+    Lenient,
+}
+
+/// The handling of consecutive observations that share a timestamp, which transmission
+/// glitches occasionally produce. Interpolation and other time-series methods on
+/// [`HDOBMessage`] assume strictly increasing times.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum DuplicateTimes {
+    /// Keep the first observation at each time and discard the repeats.
+    #[default]
+    KeepFirst,
+    /// Discard every observation at a repeated time, as there is no telling which is correct.
+    DropAll,
+    /// Reject the bulletin with [`ParseError::DuplicateTime`].
+    Error,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MergeError {
+    /// The two bulletins were transmitted by different missions.
+    MissionMismatch { left: String, right: String },
+}
+
+impl Display for MergeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissionMismatch { left, right } => {
+                write!(f, "cannot merge missions {:?} and {:?}", left, right)
+            }
+        }
+    }
+}
+
+impl Error for MergeError {}
+
+#[test]
+fn test_merge_error_display() {
+    let error: Box<dyn Error + Send + Sync> = Box::new(MergeError::MissionMismatch {
+        left: "AF307 0606A EARL".to_string(),
+        right: "AF308 1006A EARL".to_string(),
+    });
+    assert_eq!(
+        "cannot merge missions \"AF307 0606A EARL\" and \"AF308 1006A EARL\"",
+        error.to_string()
+    );
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum EncodeError {
+    /// Under [`EncodeOptions::strict_width`], the named group encoded wider than its fixed
+    /// width.
+    TooWide { field: &'static str, text: String },
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooWide { field, text } => {
+                write!(f, "{} too wide for its fixed width: {:?}", field, text)
+            }
+        }
+    }
+}
+
+impl Error for EncodeError {}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BuildError {
+    /// The observation at `index` is earlier than the one before it.
+    Unsorted { index: usize },
+    /// An [`HDOBMessageBuilder`] was given no observations.
+    Empty,
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsorted { index } => {
+                write!(f, "observation {} is earlier than the one before it", index)
+            }
+            Self::Empty => write!(f, "no observations"),
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+/// A deterministic, sortable identifier for one observation, for storage and deduplication.
+///
+/// Keys assume a mission never reports two observations for the same second, and that a
+/// retransmitted bulletin keeps its original number, so resending a bulletin reproduces its
+/// keys. Keys order by mission, then time, then bulletin number, and their [`Display`] form
+/// (`AF309 0112E KAY/20220905T155100Z/12`) sorts the same way as text.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct ObservationKey {
+    pub mission_id: String,
+    pub time: DateTime<Utc>,
+    pub obs_number: u32,
+}
+
+impl Display for ObservationKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{}/{:02}",
+            self.mission_id,
+            self.time.format("%Y%m%dT%H%M%SZ"),
+            self.obs_number
+        )
+    }
+}
+
+/// The organization whose aircraft and processing produced a bulletin.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DataSource {
+    /// The 53rd Weather Reconnaissance Squadron's WC-130Js, with missions such as `AF309`.
+    AirForceReserve,
+    /// NOAA's Aircraft Operations Center P-3s and G-IV, with missions such as `NOAA2`.
+    Noaa,
+}
+
+/// Where and when a bulletin places the storm center, and how much to trust it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CenterFix {
+    pub time: DateTime<Utc>,
+    pub location: Coordinate,
+    /// From 0 (no evidence the pass reached the center) to 1 (a clean eye crossing); see
+    /// [`HDOBMessage::estimate_center`].
+    pub confidence: f64,
+    pub method: FixMethod,
+    /// The extrapolated surface pressure at the fix.
+    pub min_pressure: Pressure,
+    /// The highest peak 10-second flight-level wind of the pass.
+    pub max_wind: Option<Speed>,
+}
+
+/// How a [`CenterFix`] was located.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum FixMethod {
+    /// The observation with the lowest extrapolated surface pressure.
+    MinimumPressure,
+}
+
+/// How many non-blank lines after the WMO header [`HDOBMessage::parse`] searches for the
+/// mission header.
+const MISSION_HEADER_LOOKAHEAD: usize = 3;
+
+/// How far, in mb, pressure must rise on both sides of a minimum for
+/// [`HDOBMessage::estimate_center`] to consider it fully sharp.
+const SHARP_MINIMUM_MB: f64 = 5.0;
+
+/// The motion of the storm center between the fixes estimated from two bulletins, or `None` if
+/// either has no fix or both fixes are at the same time. The bulletins may be given in either
+/// order.
+///
+/// Unlike a reported wind, the direction is the heading the center is moving *toward*, as
+/// storm motion is conventionally given.
+pub fn storm_motion(a: &HDOBMessage, b: &HDOBMessage) -> Option<Wind> {
+    let (a, b) = (a.estimate_center()?, b.estimate_center()?);
+    let (from, to) = if a.time <= b.time { (a, b) } else { (b, a) };
+    let hours = (to.time - from.time).num_seconds() as f64 / 3600.0;
+    if hours <= 0.0 {
+        return None;
+    }
+    let knots = from.location.distance_to(&to.location) / hours;
+    Some(Wind::with_direction_and_speed(
+        from.location.bearing_to(&to.location),
+        Speed::with_knots(knots.round() as u32),
+    ))
+}
+
+/// Which winds to measure [`HDOBMessage::radius_of_max_wind`] with.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum WindSource {
+    /// The peak 10-second flight-level wind.
+    #[default]
+    FlightLevel,
+    /// The peak 10-second SFMR surface wind.
+    Sfmr,
+}
+
+/// Sums of the east and north components of the wind directions reported in `obs`, each scaled
+/// by `magnitude`, with the number of winds summed.
+fn wind_components(obs: &[HDHALog], magnitude: impl Fn(&Wind) -> f64) -> (f64, f64, usize) {
+    obs.iter()
+        .filter_map(|ob| ob.wind)
+        .fold((0.0, 0.0, 0), |(u, v, n), w| {
+            let theta = w.direction.angle().decimal_degrees().to_radians();
+            let m = magnitude(&w);
+            (u + m * theta.sin(), v + m * theta.cos(), n + 1)
+        })
+}
+
+/// The vector average of the flight-level winds in `obs`, or `None` if none were reported or
+/// they cancel out entirely.
+fn vector_mean_wind(obs: &[HDHALog]) -> Option<Wind> {
+    let (u, v, n) = wind_components(obs, |w| w.speed.knots_f64());
+    if n == 0 || (u == 0.0 && v == 0.0) {
+        return None;
+    }
+    Some(Wind::with_direction_and_speed(
+        Direction::from_degrees(u.atan2(v).to_degrees()),
+        Speed::with_knots_f64(u.hypot(v) / n as f64),
+    ))
+}
+
+/// The rate of change of extrapolated surface pressure, in mb/hr, from the first to the last
+/// observation of `window`. Returns `None` if either lacks an extrapolated surface pressure or
+/// they are at the same time.
+pub fn tendency(window: &[HDHALog]) -> Option<f64> {
+    let (first, last) = (window.first()?, window.last()?);
+    let pressure = |ob: &HDHALog| match ob.surface_pressure {
+        Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(p)) => Some(p),
+        _ => None,
+    };
+    let hours = (last.time - first.time).num_milliseconds() as f64 / 3_600_000.0;
+    if hours == 0.0 {
+        return None;
+    }
+    let change = pressure(last)?.microbars() - pressure(first)?.microbars();
+    Some(change as f64 / 1000.0 / hours)
+}
+
+/// A reconnaissance bulletin, as sorted by [`classify`].
+///
+/// Only HDOB is parsed so far; the other products carry their text unparsed, so they can at
+/// least be routed and stored.
+#[derive(Debug)]
+pub enum ReconProduct {
+    /// High density observations (`URNT15`/`URPN15`).
+    Hdob(HDOBMessage),
+    /// A vortex data message (`URNT12`/`URPN12`, AWIPS `REPNT2`/`REPPN2`).
+    Vdm(String),
+    /// A dropsonde TEMP DROP message (`UZNT13`/`UZPN13`, AWIPS `REPNT3`/`REPPN3`).
+    Dropsonde(String),
+    /// A RECCO observation (`URNT11`/`URPN11`, AWIPS `REPNT1`/`REPPN1`).
+    Recco(String),
+}
+
+/// Identifies a reconnaissance bulletin from its WMO heading, or the AWIPS identifier on the
+/// line after it, and parses it accordingly.
+pub fn classify(bulletin: &str) -> Result<ReconProduct, ParseError> {
+    let mut lines = bulletin
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| !WMO_RE.is_match(line));
+    let header = lines.next().ok_or(ParseError::Missing("header"))?;
+    let awips = lines.next().unwrap_or("");
+    let product = |wmo: &[&str], awips_ids: &[&str]| {
+        wmo.iter().any(|id| header.starts_with(id)) || awips_ids.contains(&awips)
+    };
+
+    if product(&["URNT15", "URPN15"], &[]) {
+        HDOBMessage::parse(bulletin).map(ReconProduct::Hdob)
+    } else if product(&["URNT12", "URPN12"], &["REPNT2", "REPPN2"]) {
+        Ok(ReconProduct::Vdm(bulletin.to_string()))
+    } else if product(&["UZNT13", "UZPN13"], &["REPNT3", "REPPN3"]) {
+        Ok(ReconProduct::Dropsonde(bulletin.to_string()))
+    } else if product(&["URNT11", "URPN11"], &["REPNT1", "REPPN1"]) {
+        Ok(ReconProduct::Recco(bulletin.to_string()))
+    } else {
+        Err(ParseError::invalid("product", header))
+    }
+}
+
+#[test]
+fn test_classify() {
+    let kay = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+    match classify(kay) {
+        Ok(ReconProduct::Hdob(message)) => {
+            assert_eq!("AF309 0112E KAY", message.mission_id);
+            assert_eq!(HDOBMessage::parse(kay).unwrap().obs, message.obs);
+        }
+        other => panic!("expected HDOB, found {:?}", other),
+    }
+
+    let vdm = "URNT12 KNHC 051610\nREPNT2\nVORTEX DATA MESSAGE AL122022\n";
+    assert!(matches!(classify(vdm), Ok(ReconProduct::Vdm(_))));
+    let drop = "UZNT13 KNHC 051612\nXXAA 55167 99261 70938 08026\n";
+    assert!(matches!(classify(drop), Ok(ReconProduct::Dropsonde(_))));
+
+    assert_eq!(
+        Some(ParseError::invalid("product", "WTNT42 KNHC 051500")),
+        classify("WTNT42 KNHC 051500\nTROPICAL STORM KAY DISCUSSION\n").err()
+    );
+    assert_eq!(
+        Some(ParseError::Missing("header")),
+        classify("AF309 0112E KAY HDOB 12 20220905").err()
+    );
+}
+
+/// The radius, in nautical miles, of the inner core used by
+/// [`HDOBMessage::pressure_gradient`].
+pub const INNER_CORE_RADIUS_NM: f64 = 30.0;
+
+/// How far, in mb, the aircraft pressure may wander during a leg that
+/// [`HDOBMessage::vertical_legs`] labels level.
+pub const LEVEL_TOLERANCE_MB: f64 = 5.0;
+
+/// Whether the aircraft held its level, climbed, or descended over a leg.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum VerticalMode {
+    Level,
+    /// Climbing, with the aircraft pressure falling.
+    Ascending,
+    /// Descending, with the aircraft pressure rising, as when profiling down to a low level.
+    Descending,
+}
+
+/// A turning point in a series, as found by [`HDOBMessage::wind_extrema`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Extremum {
+    Maximum,
+    Minimum,
+}
+
 #[derive(Debug)]
 pub struct HDOBMessage {
     pub header: String,
     pub mission_id: String,
     pub obs_number: u32,
-    pub date: Date<Utc>,
+    pub date: NaiveDate,
     pub obs: Vec<HDHALog>,
 }
 
-impl HDOBMessage {
-    pub fn parse(hdob: &str) -> Self {
-        let mut lines = hdob.lines().skip(1);
-        let header = lines.next().unwrap().trim().to_string();
-        let mission_header = lines.next().expect("No header");
-        let re =
-            Regex::new(r"([A-Z0-9 ]*) HDOB ([0-9]{2}) ([0-9]{4})([0-9]{2})([0-9]{2})").unwrap();
-        let captures = re.captures(mission_header).unwrap();
-        let mission_id = captures.get(1).unwrap().as_str().trim().to_string();
-        let obs_number = captures
-            .get(2)
-            .unwrap()
-            .as_str()
-            .parse()
-            .expect("Unable to parse obs number");
-        let y = captures.get(3).unwrap().as_str().parse().unwrap();
-        let m = captures.get(4).unwrap().as_str().parse().unwrap();
-        let d = captures.get(5).unwrap().as_str().parse().unwrap();
-        let date = Utc.ymd(y, m, d);
-        let mut obs = vec![];
-        for line in lines {
-            if line == "$$" {
-                break;
-            }
-            let log = HDHALog::parse(&date, line);
-            obs.push(log);
-        }
+impl HDOBMessage {
+    /// Assembles a bulletin from observations already in hand, such as synthesized or filtered
+    /// ones. The observations must be in time order; several may share a time. Unsorted input
+    /// is rejected rather than silently reordered, since it usually means observations from
+    /// different passes were mixed up; sort by `time` first if reordering is intended.
+    pub fn from_obs(
+        header: String,
+        mission_id: String,
+        obs_number: u32,
+        date: NaiveDate,
+        obs: Vec<HDHALog>,
+    ) -> Result<Self, BuildError> {
+        if let Some(i) = obs.windows(2).position(|pair| pair[1].time < pair[0].time) {
+            return Err(BuildError::Unsorted { index: i + 1 });
+        }
+        Ok(Self {
+            header,
+            mission_id,
+            obs_number,
+            date,
+            obs,
+        })
+    }
+
+    pub fn parse(hdob: &str) -> Result<Self, ParseError> {
+        Self::parse_with(hdob, &ParseOptions::default())
+    }
+
+    /// Renders the bulletin as HDOB text: the WMO header, the mission header, a line per
+    /// observation, and the `$$` terminator.
+    ///
+    /// Under [`EncodeOptions::strict_width`], a parsed bulletin is reproduced byte for byte
+    /// from its WMO header through `$$`, and a group too wide for its fixed width is an error
+    /// rather than being written as is. Teletype framing around the bulletin, such as a leading
+    /// sequence number or a trailing `;`, is not kept by parsing and is never written.
+    pub fn to_bulletin(&self, options: &EncodeOptions) -> Result<String, EncodeError> {
+        let fit = |field: &'static str, text: String, width: usize| {
+            if options.strict_width && text.len() > width {
+                Err(EncodeError::TooWide { field, text })
+            } else {
+                Ok(text)
+            }
+        };
+
+        let mut text = format!(
+            "{}\n{:<30} HDOB {} {}\n",
+            self.header,
+            fit("mission id", self.mission_id.clone(), 30)?,
+            fit("obs number", format!("{:02}", self.obs_number), 2)?,
+            self.date.format("%Y%m%d")
+        );
+        for ob in &self.obs {
+            let line = ob.to_hdob(options);
+            for (column, token) in HDOB_COLUMNS.iter().zip(line.split(' ')) {
+                fit(column.name, token.to_string(), column.width)?;
+            }
+            text += &line;
+            text.push('\n');
+        }
+        text += "$$\n";
+        Ok(text)
+    }
+
+    /// Decompresses and parses a gzip-compressed bulletin, such as an archived `.txt.gz` file.
+    #[cfg(feature = "gzip")]
+    pub fn parse_gz<R: io::Read>(r: R) -> Result<Self, ParseError> {
+        use std::io::Read;
+
+        let mut hdob = String::new();
+        flate2::read::MultiGzDecoder::new(r).read_to_string(&mut hdob)?;
+        Self::parse(&hdob)
+    }
+
+    /// Parses every bulletin file in a directory, in filename order, pairing each result with
+    /// its path. Files ending `.txt` are read as text and, with the `gzip` feature, files ending
+    /// `.txt.gz` are decompressed; anything else is skipped.
+    ///
+    /// Returns an error only if the directory itself cannot be listed.
+    pub fn parse_dir(
+        path: &Path,
+    ) -> Result<impl Iterator<Item = (PathBuf, Result<Self, ParseError>)>, ParseError> {
+        let mut paths = std::fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        paths.retain(|path| {
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("");
+            name.ends_with(".txt") || (cfg!(feature = "gzip") && name.ends_with(".txt.gz"))
+        });
+        paths.sort();
+        Ok(paths.into_iter().map(|path| {
+            let result = Self::parse_file(&path);
+            (path, result)
+        }))
+    }
+
+    fn parse_file(path: &Path) -> Result<Self, ParseError> {
+        #[cfg(feature = "gzip")]
+        if path.extension().is_some_and(|ext| ext == "gz") {
+            return Self::parse_gz(std::fs::File::open(path)?);
+        }
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parses a stream of consecutive bulletins, such as a relay feed or a concatenated archive,
+    /// yielding each as its `$$` terminator (or the end of the input) is reached.
+    ///
+    /// A bulletin that fails to parse yields an error and reading continues with the next. A
+    /// read error ends the stream after being yielded.
+    pub fn from_reader<R: io::BufRead>(r: R) -> impl Iterator<Item = Result<Self, ParseError>> {
+        let mut lines = r.lines();
+        let mut splitter = BulletinSplitter::default();
+        let mut done = false;
+        std::iter::from_fn(move || {
+            while !done {
+                match lines.next() {
+                    Some(Ok(line)) => {
+                        if let Some(bulletin) = splitter.push(&line) {
+                            return Some(Self::parse(&bulletin));
+                        }
+                    }
+                    Some(Err(e)) => {
+                        done = true;
+                        return Some(Err(e.into()));
+                    }
+                    None => {
+                        done = true;
+                        return splitter.finish().map(|bulletin| Self::parse(&bulletin));
+                    }
+                }
+            }
+            None
+        })
+    }
+
+    /// Parses every bulletin in `text`, such as a day's archive holding several aircraft's
+    /// bulletins one after another. See [`HDOBMessage::from_reader`].
+    pub fn parse_many(text: &str) -> Vec<Result<Self, ParseError>> {
+        Self::from_reader(text.as_bytes()).collect()
+    }
+
+    /// The asynchronous counterpart of [`HDOBMessage::from_reader`], for reading bulletins from
+    /// a socket or file without blocking the runtime.
+    #[cfg(feature = "tokio")]
+    pub fn stream<R: tokio::io::AsyncBufRead + Unpin>(
+        r: R,
+    ) -> impl futures_util::Stream<Item = Result<Self, ParseError>> {
+        use tokio::io::AsyncBufReadExt;
+
+        let state = (r.lines(), BulletinSplitter::default(), false);
+        futures_util::stream::unfold(state, |(mut lines, mut splitter, done)| async move {
+            if done {
+                return None;
+            }
+            loop {
+                match lines.next_line().await {
+                    Ok(Some(line)) => {
+                        if let Some(bulletin) = splitter.push(&line) {
+                            return Some((Self::parse(&bulletin), (lines, splitter, false)));
+                        }
+                    }
+                    Err(e) => return Some((Err(e.into()), (lines, splitter, true))),
+                    Ok(None) => {
+                        let bulletin = splitter.finish()?;
+                        return Some((Self::parse(&bulletin), (lines, splitter, true)));
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn parse_with(hdob: &str, options: &ParseOptions) -> Result<Self, ParseError> {
+        Self::parse_lines(hdob, options, |_, error| Err(error))
+    }
+
+    /// Parses as much of a bulletin as possible, for tools that would rather report problems
+    /// than stop at them. Data lines that can't be parsed are skipped, unknown quality codes
+    /// are handled as with [`QualityMode::Lenient`], and repeated times as with
+    /// [`DuplicateTimes::KeepFirst`].
+    ///
+    /// Alongside the bulletin are warnings, in this order, for the skipped lines, observations
+    /// with quality flags set, failed [`HDHALog::sanity_check`]s, and [`HDOBMessage::gaps`].
+    ///
+    /// This never fails. If the headers are unusable or no observation can be parsed, the
+    /// message has no observations and blank headers, dated 1970-01-01, and the warnings end
+    /// with [`Warning::Unparsable`] giving the reason.
+    pub fn parse_lenient(hdob: &str) -> (Self, Vec<Warning>) {
+        let options = ParseOptions {
+            quality_mode: QualityMode::Lenient,
+            duplicate_times: DuplicateTimes::KeepFirst,
+            ..Default::default()
+        };
+        let mut warnings = vec![];
+        let parsed = Self::parse_lines(hdob, &options, |line, error| {
+            warnings.push(Warning::SkippedLine { line, error });
+            Ok(())
+        });
+        let message = match parsed {
+            Ok(message) => message,
+            Err(error) => {
+                warnings.push(Warning::Unparsable(error));
+                let message = HDOBMessage {
+                    header: String::new(),
+                    mission_id: String::new(),
+                    obs_number: 0,
+                    date: NaiveDate::default(),
+                    obs: vec![],
+                };
+                return (message, warnings);
+            }
+        };
+
+        for (ob, flags) in message.questionable_obs() {
+            warnings.push(Warning::Questionable {
+                time: ob.time,
+                flags,
+            });
+        }
+        for ob in &message.obs {
+            for violation in ob.sanity_check() {
+                warnings.push(Warning::Sanity {
+                    time: ob.time,
+                    violation,
+                });
+            }
+        }
+        for (before, after) in message.gaps() {
+            warnings.push(Warning::Gap {
+                from: before.time,
+                to: after.time,
+            });
+        }
+        (message, warnings)
+    }
+
+    /// Parses a bulletin, passing each data line that fails to parse, with its 1-based line
+    /// number, to `bad_line`, which either skips it or fails the whole bulletin.
+    fn parse_lines(
+        hdob: &str,
+        options: &ParseOptions,
+        mut bad_line: impl FnMut(usize, ParseError) -> Result<(), ParseError>,
+    ) -> Result<Self, ParseError> {
+        lazy_static! {
+            static ref RE: Regex = Regex::new(r"([A-Z0-9 ]*) HDOB ([0-9]{1,2}) ([0-9]+)").unwrap();
+            static ref DATE_RE: Regex = Regex::new(r"^([0-9]{4})([0-9]{2})([0-9]{2})$").unwrap();
+        }
+
+        // Bulletins relayed over teletype-derived links may carry CRLF line endings, a BOM, or
+        // SOH/ETX framing characters, and may be preceded by a sequence number or blank lines.
+        let hdob: String = hdob
+            .chars()
+            .filter(|c| *c == '\n' || !(c.is_control() || *c == '\u{feff}'))
+            .collect();
+        if hdob.trim().is_empty() {
+            return Err(ParseError::Empty);
+        }
+        let mut lines = hdob
+            .lines()
+            .map(str::trim)
+            .enumerate()
+            .skip_while(|(_, line)| !WMO_RE.is_match(line));
+        let (_, header) = lines.next().ok_or(ParseError::Missing("header"))?;
+        let header = header.to_string();
+        // Some relays insert blank lines or a product identifier between the WMO header and
+        // the mission header, so look a few lines ahead for it
+        let mut following = lines
+            .by_ref()
+            .filter(|(_, line)| !line.is_empty())
+            .take(MISSION_HEADER_LOOKAHEAD)
+            .peekable();
+        let Some(&(_, first)) = following.peek() else {
+            return Err(ParseError::Empty);
+        };
+        let captures = following
+            .find_map(|(_, line)| RE.captures(line))
+            .ok_or_else(|| ParseError::invalid("mission header", first))?;
+        let mission_id = captures.get(1).unwrap().as_str().trim().to_string();
+        // Some relays strip the leading zero from the bulletin number. The date can't be
+        // recovered if they do the same to it (is 2022911 Sept. 11 or Nov. 1?), so it must be
+        // complete.
+        let obs_number = captures.get(2).unwrap().as_str().parse().unwrap();
+        let date_text = captures.get(3).unwrap().as_str();
+        let date = DATE_RE
+            .captures(date_text)
+            .and_then(|date| {
+                // Fixed-width digits, so these parses cannot fail
+                let y = date.get(1).unwrap().as_str().parse().unwrap();
+                let m = date.get(2).unwrap().as_str().parse().unwrap();
+                let d = date.get(3).unwrap().as_str().parse().unwrap();
+                NaiveDate::from_ymd_opt(y, m, d)
+            })
+            .ok_or_else(|| ParseError::invalid("date", date_text))?;
+        let mut obs = vec![];
+        for (index, line) in lines {
+            if line == "$$" {
+                break;
+            }
+            if line.is_empty() {
+                continue;
+            }
+            match HDHALog::parse_with(&date, line, options) {
+                Ok(log) => obs.push(HDHALog {
+                    source_line: index + 1,
+                    ..log
+                }),
+                Err(error) => bad_line(index + 1, error)?,
+            }
+        }
+        if obs.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        match options.expected_obs_count {
+            Some(expected) if expected != obs.len() => {
+                return Err(ParseError::ObsCountMismatch {
+                    expected,
+                    found: obs.len(),
+                })
+            }
+            _ => {}
+        }
+        let obs = dedup_times(obs, options.duplicate_times)?;
+        if obs.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        Ok(Self {
+            header,
+            mission_id,
+            obs_number,
+            date,
+            obs,
+        })
+    }
+
+    /// Which organization flew the mission, from the aircraft identifier that begins the
+    /// mission header, or `None` for an unrecognized identifier.
+    ///
+    /// HDOB data lines carry no provenance of their own, so this applies to every observation
+    /// in the bulletin.
+    pub fn data_source(&self) -> Option<DataSource> {
+        if self.mission_id.starts_with("AF") {
+            Some(DataSource::AirForceReserve)
+        } else if self.mission_id.starts_with("NOAA") {
+            Some(DataSource::Noaa)
+        } else {
+            None
+        }
+    }
+
+    /// The ICAO identifier of the center that issued the bulletin, e.g. `KNHC`, taken from the
+    /// WMO header.
+    pub fn issuing_center(&self) -> Option<&str> {
+        self.header.split_whitespace().nth(1)
+    }
+
+    /// When the bulletin was issued, per the WMO header.
+    ///
+    /// The header gives only day, hour, and minute (`051606`), so the year and month are taken
+    /// from the bulletin's date, moving to the adjacent month when the day has rolled over
+    /// (a bulletin dated the 30th issued on the 1st).
+    pub fn issuance_time(&self) -> Option<DateTime<Utc>> {
+        let ddhhmm = self.header.split_whitespace().nth(2)?;
+        if ddhhmm.len() != 6 || !ddhhmm.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let field = |range: std::ops::Range<usize>| ddhhmm[range].parse::<u32>().unwrap();
+        let (day, hour, minute) = (field(0..2), field(2..4), field(4..6));
+
+        let anchor = self.date.and_hms_opt(12, 0, 0)?.and_utc();
+        let first = self.date.with_day(1)?;
+        [
+            first.checked_sub_months(Months::new(1)),
+            Some(first),
+            first.checked_add_months(Months::new(1)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|month| month.with_day(day)?.and_hms_opt(hour, minute, 0))
+        .map(|issued| issued.and_utc())
+        .min_by_key(|issued| (*issued - anchor).abs())
+    }
+
+    /// How long after its last observation the bulletin was issued, a proxy for data latency.
+    /// The issuance time is only given to the minute, so this may be slightly negative.
+    pub fn transmission_delay(&self) -> Option<Duration> {
+        Some(self.issuance_time()? - self.last_obs()?.time)
+    }
+
+    /// The observation at `index`, or `None` if it is out of range.
+    pub fn obs_at(&self, index: usize) -> Option<&HDHALog> {
+        self.obs.get(index)
+    }
+
+    /// The observation closest in time to `t`, the earlier on a tie, or `None` if the bulletin
+    /// holds none. Relies on the observations being in time order.
+    pub fn obs_nearest(&self, t: DateTime<Utc>) -> Option<&HDHALog> {
+        let i = self.obs.partition_point(|ob| ob.time < t);
+        let after = self.obs.get(i);
+        let before = i.checked_sub(1).and_then(|i| self.obs.get(i));
+        match (before, after) {
+            (Some(before), Some(after)) if after.time - t < t - before.time => Some(after),
+            (Some(before), _) => Some(before),
+            (None, after) => after,
+        }
+    }
+
+    /// The earliest observation in the bulletin, if it holds any.
+    pub fn first_obs(&self) -> Option<&HDHALog> {
+        self.obs.first()
+    }
+
+    /// The latest observation in the bulletin, if it holds any.
+    pub fn last_obs(&self) -> Option<&HDHALog> {
+        self.obs.last()
+    }
+
+    /// The storage key of `ob`, one of this bulletin's observations.
+    pub fn observation_key(&self, ob: &HDHALog) -> ObservationKey {
+        ObservationKey {
+            mission_id: self.mission_id.clone(),
+            time: ob.time,
+            obs_number: self.obs_number,
+        }
+    }
+
+    /// The observation with the lowest extrapolated surface pressure, if any carry one.
+    /// Implausible pressures, outside the range of [`SurfacePressure`], are ignored.
+    pub fn min_pressure_obs(&self) -> Option<&HDHALog> {
+        self.obs
+            .iter()
+            .filter_map(|ob| match ob.surface_pressure {
+                Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(p)) => {
+                    SurfacePressure::new(p).map(|p| (ob, p))
+                }
+                _ => None,
+            })
+            .min_by_key(|(_, p)| *p)
+            .map(|(ob, _)| ob)
+    }
+
+    /// The observation with the lightest flight-level wind inside the eye, the wind center.
+    ///
+    /// The eye is taken to span from the strongest wind before the pressure center to the
+    /// strongest wind after it, the pressure center being the lowest extrapolated surface
+    /// pressure or, for high-altitude passes reporting D-values, the lowest D-value. Its offset
+    /// from the pressure center hints at vortex tilt. `None` if there is no pressure center or
+    /// no wind within the eye.
+    pub fn wind_center(&self) -> Option<&HDHALog> {
+        let center = match self.min_pressure_obs() {
+            Some(ob) => self.obs.iter().position(|it| std::ptr::eq(it, ob))?,
+            None => {
+                self.obs
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, ob)| match ob.surface_pressure {
+                        Some(ExtrapolatedSurfacePressure::DValue(d)) => Some((i, d)),
+                        _ => None,
+                    })
+                    .min_by_key(|(_, d)| *d)?
+                    .0
+            }
+        };
+
+        let speed = |ob: &HDHALog| ob.wind.map(|w| w.speed);
+        // Ties go to the peak nearest the center
+        let start = self.obs[..center]
+            .iter()
+            .enumerate()
+            .filter(|(_, ob)| ob.wind.is_some())
+            .max_by_key(|(_, ob)| speed(ob))
+            .map_or(0, |(i, _)| i);
+        let end = self.obs[center + 1..]
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, ob)| ob.wind.is_some())
+            .max_by_key(|(_, ob)| speed(ob))
+            .map_or(self.obs.len() - 1, |(i, _)| center + 1 + i);
+
+        self.obs[start..=end]
+            .iter()
+            .filter(|ob| ob.wind.is_some())
+            .min_by_key(|ob| speed(ob))
+    }
+
+    /// Estimates the storm center as the position of the lowest extrapolated surface pressure,
+    /// or `None` if no observation carries one. This is only meaningful for a pass that
+    /// actually crosses the center, which the fix's confidence tries to judge.
+    ///
+    /// The confidence averages two scores between 0 and 1. The pressure score is the smaller of
+    /// the rises in pressure before and after the minimum, as a fraction of 5 mb, and is 0 when
+    /// the minimum ends the pass, as on a shoulder pass that never reached the center. The wind
+    /// score is `(1 − cos Δ) / 2`, where `Δ` is the angle between the mean flight-level winds
+    /// before and after the minimum: 1 for the full reversal of a crossing, 0 for a steady wind.
+    pub fn estimate_center(&self) -> Option<CenterFix> {
+        let ob = self.min_pressure_obs()?;
+        let i = self.obs.iter().position(|it| std::ptr::eq(it, ob))?;
+        let min_pressure = match ob.surface_pressure {
+            Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(p)) => p,
+            _ => return None,
+        };
+        let (before, after) = (&self.obs[..i], &self.obs[i + 1..]);
+
+        let rise = |side: &[HDHALog]| {
+            side.iter()
+                .filter_map(|ob| match ob.surface_pressure {
+                    Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(p)) => {
+                        SurfacePressure::new(p)
+                    }
+                    _ => None,
+                })
+                .max()
+                .map(|p| (p.pressure().microbars() - min_pressure.microbars()) as f64 / 1000.0)
+        };
+        let pressure_score = match (rise(before), rise(after)) {
+            (Some(a), Some(b)) => (a.min(b) / SHARP_MINIMUM_MB).min(1.0),
+            _ => 0.0,
+        };
+        let wind_score = match (vector_mean_wind(before), vector_mean_wind(after)) {
+            (Some(a), Some(b)) => {
+                let delta = (a.direction.angle().decimal_degrees()
+                    - b.direction.angle().decimal_degrees())
+                .to_radians();
+                (1.0 - delta.cos()) / 2.0
+            }
+            _ => 0.0,
+        };
+
+        Some(CenterFix {
+            time: ob.time,
+            location: ob.location,
+            confidence: (pressure_score + wind_score) / 2.0,
+            method: FixMethod::MinimumPressure,
+            min_pressure,
+            max_wind: self.peak_wind_obs().and_then(|ob| ob.peak_wind_speed),
+        })
+    }
+
+    /// The observation with the highest peak 10-second flight-level wind, if any carry one.
+    pub fn peak_wind_obs(&self) -> Option<&HDHALog> {
+        self.obs
+            .iter()
+            .filter_map(|ob| ob.peak_wind_speed.map(|speed| (ob, speed)))
+            .max_by_key(|(_, speed)| *speed)
+            .map(|(ob, _)| ob)
+    }
+
+    /// The distance in nautical miles from `center` to the strongest `source` wind of the pass,
+    /// or `None` if no observation reports that wind.
+    ///
+    /// This is the radius of maximum wind only if the pass ran through the center; otherwise it
+    /// overestimates it.
+    pub fn radius_of_max_wind(&self, center: &Coordinate, source: WindSource) -> Option<f64> {
+        let speed = |ob: &HDHALog| match source {
+            WindSource::FlightLevel => ob.peak_wind_speed,
+            WindSource::Sfmr => ob.peak_sfmr_speed,
+        };
+        self.obs
+            .iter()
+            .filter_map(|ob| speed(ob).map(|speed| (ob, speed)))
+            .max_by_key(|(_, speed)| *speed)
+            .map(|(ob, _)| center.distance_to(&ob.location))
+    }
+
+    /// The surface pressure gradient of the inner core: the least-squares rate of change, in mb
+    /// per nautical mile, of [`HDHALog::resolved_surface_pressure`] moving toward `center`,
+    /// from the observations within [`INNER_CORE_RADIUS_NM`] of it. Pressure falling toward the
+    /// center makes this negative, more so the tighter and more intense the core.
+    ///
+    /// Returns `None` unless at least two such observations are at different distances.
+    pub fn pressure_gradient(&self, center: &Coordinate) -> Option<f64> {
+        let points: Vec<(f64, f64)> = self
+            .obs
+            .iter()
+            .filter_map(|ob| {
+                let r = center.distance_to(&ob.location);
+                let p = ob.resolved_surface_pressure()?.pressure().microbars() as f64 / 1000.0;
+                (r <= INNER_CORE_RADIUS_NM).then_some((r, p))
+            })
+            .collect();
+        let n = points.len() as f64;
+        let mean_r = points.iter().map(|(r, _)| r).sum::<f64>() / n;
+        let mean_p = points.iter().map(|(_, p)| p).sum::<f64>() / n;
+        let (cov, var) = points.iter().fold((0.0, 0.0), |(cov, var), (r, p)| {
+            (
+                cov + (r - mean_r) * (p - mean_p),
+                var + (r - mean_r).powi(2),
+            )
+        });
+        // Outward slope, negated to point inward
+        (points.len() >= 2 && var > 0.0).then(|| -cov / var)
+    }
+
+    /// The vector average of the flight-level winds over the pass, or `None` if none were
+    /// reported or they cancel out entirely.
+    pub fn mean_wind(&self) -> Option<Wind> {
+        vector_mean_wind(&self.obs)
+    }
+
+    /// A crude proxy for the vertical wind shear between the highest and lowest levels flown:
+    /// the vector difference of the mean flight-level winds over the level legs of
+    /// [`HDOBMessage::vertical_legs`], upper minus lower.
+    ///
+    /// Like any wind, the result is named for the direction it comes from, so upper westerlies
+    /// over lower southerlies give a shear from the west-northwest. Returns `None` unless at
+    /// least two levels were flown, each with a mean wind.
+    pub fn level_shear(&self) -> Option<Wind> {
+        let levels: Vec<&[HDHALog]> = self
+            .vertical_legs()
+            .into_iter()
+            .filter(|(mode, _)| *mode == VerticalMode::Level)
+            .map(|(_, leg)| leg)
+            .collect();
+        let upper = levels.iter().min_by_key(|leg| leg[0].aircraft_pressure)?;
+        let lower = levels.iter().max_by_key(|leg| leg[0].aircraft_pressure)?;
+        let depth = lower[0].aircraft_pressure.microbars() - upper[0].aircraft_pressure.microbars();
+        if depth as f64 / 1000.0 <= LEVEL_TOLERANCE_MB {
+            return None;
+        }
+
+        let (upper, lower) = (vector_mean_wind(upper)?, vector_mean_wind(lower)?);
+        let components = |w: Wind| {
+            let theta = w.direction.angle().decimal_degrees().to_radians();
+            let kt = w.speed.knots_f64();
+            (kt * theta.sin(), kt * theta.cos())
+        };
+        let ((u1, v1), (u0, v0)) = (components(upper), components(lower));
+        let (u, v) = (u1 - u0, v1 - v0);
+        Some(Wind::with_direction_and_speed(
+            Direction::from_degrees(u.atan2(v).to_degrees()),
+            Speed::with_knots_f64(u.hypot(v)),
+        ))
+    }
+
+    /// The mean flight-level wind direction and its circular standard deviation in degrees,
+    /// `√(−2 ln R)` where `R` is the length of the mean of the directions as unit vectors.
+    /// Speeds are ignored, so light and strong winds count alike.
+    ///
+    /// The deviation is near zero for steady flow and grows without bound as directions
+    /// scatter; `None` is returned if no winds were reported or their directions cancel out.
+    pub fn circular_stats(&self) -> Option<(Direction, f64)> {
+        let (u, v, n) = wind_components(&self.obs, |_| 1.0);
+        if n == 0 || (u == 0.0 && v == 0.0) {
+            return None;
+        }
+        let r = (u.hypot(v) / n as f64).min(1.0);
+        Some((
+            Direction::from_degrees(u.atan2(v).to_degrees()),
+            (-2.0 * r.ln()).sqrt().to_degrees(),
+        ))
+    }
+
+    /// Whether the pass appears to cross an eye: the minimum-pressure observation lies between
+    /// flight-level winds at least twice its own on both sides.
+    pub fn crosses_eye(&self) -> bool {
+        let Some(center) = self.min_pressure_obs() else {
+            return false;
+        };
+        let Some(i) = self.obs.iter().position(|ob| std::ptr::eq(ob, center)) else {
+            return false;
+        };
+        let calm = center.wind.map_or(0, |w| w.speed.knots());
+        let peak = |obs: &[HDHALog]| {
+            obs.iter()
+                .filter_map(|ob| ob.wind.map(|w| w.speed.knots()))
+                .max()
+                .unwrap_or(0)
+        };
+        let (before, after) = self.obs.split_at(i);
+        let threshold = (2 * calm).max(1);
+        peak(before) >= threshold && peak(&after[1..]) >= threshold
+    }
+
+    /// Splits the pass into runs flown at a steady level and the climbs or descents between
+    /// them, in order.
+    ///
+    /// A level run ends at the first observation whose aircraft pressure differs from the run's
+    /// first by more than `tolerance_mb`. The following climb or descent lasts until two
+    /// consecutive observations are again within `tolerance_mb` of each other, which begin the
+    /// next level run. A sudden step between levels produces no climb segment of its own.
+    pub fn segments_by_level(&self, tolerance_mb: f64) -> Vec<&[HDHALog]> {
+        let mb = |i: usize| self.obs[i].aircraft_pressure.microbars() as f64 / 1000.0;
+        let mut segments = vec![];
+        let mut start = 0;
+        let mut changing_level = false;
+        for i in 1..self.obs.len() {
+            if !changing_level {
+                if (mb(i) - mb(start)).abs() > tolerance_mb {
+                    segments.push(&self.obs[start..i]);
+                    start = i;
+                    changing_level = true;
+                }
+            } else if (mb(i) - mb(i - 1)).abs() <= tolerance_mb {
+                if i - 1 > start {
+                    segments.push(&self.obs[start..i - 1]);
+                }
+                start = i - 1;
+                changing_level = false;
+            }
+        }
+        if start < self.obs.len() {
+            segments.push(&self.obs[start..]);
+        }
+        segments
+    }
+
+    /// Splits the pass as [`HDOBMessage::segments_by_level`] does, with a tolerance of
+    /// [`LEVEL_TOLERANCE_MB`], and labels each segment by the change in aircraft pressure from
+    /// its first observation to its last. A segment of a single observation is labeled by the
+    /// change to the next observation, as it begins a climb or descent.
+    pub fn vertical_legs(&self) -> Vec<(VerticalMode, &[HDHALog])> {
+        let mut legs = vec![];
+        let mut start = 0;
+        for segment in self.segments_by_level(LEVEL_TOLERANCE_MB) {
+            let end = if segment.len() == 1 {
+                (start + 1).min(self.obs.len() - 1)
+            } else {
+                start + segment.len() - 1
+            };
+            let change = (self.obs[end].aircraft_pressure.microbars()
+                - self.obs[start].aircraft_pressure.microbars()) as f64
+                / 1000.0;
+            let mode = if change.abs() <= LEVEL_TOLERANCE_MB {
+                VerticalMode::Level
+            } else if change > 0.0 {
+                VerticalMode::Descending
+            } else {
+                VerticalMode::Ascending
+            };
+            legs.push((mode, segment));
+            start += segment.len();
+        }
+        legs
+    }
+
+    /// Indices of the local maxima and minima of the flight-level wind speed, in order, such as
+    /// the eyewalls and eye of each crossing or the peaks of rainbands.
+    ///
+    /// A turning point only counts once the wind has moved at least `prominence` away from it,
+    /// which suppresses gust-scale noise; it should be positive. The ends of the pass are never
+    /// extrema, and observations without a wind are skipped.
+    pub fn wind_extrema(&self, prominence: Speed) -> Vec<(usize, Extremum)> {
+        let p = prominence.knots();
+        let mut speeds = self
+            .obs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, ob)| ob.wind.map(|w| (i, w.speed.knots())));
+        let Some(first) = speeds.next() else {
+            return vec![];
+        };
+
+        let mut extrema = vec![];
+        let (mut high, mut low) = (first, first);
+        let mut seeking = None;
+        for (i, kt) in speeds {
+            if kt > high.1 {
+                high = (i, kt);
+            }
+            if kt < low.1 {
+                low = (i, kt);
+            }
+            match seeking {
+                None if high.1 - low.1 >= p => {
+                    seeking = Some(if high.0 > low.0 {
+                        Extremum::Maximum
+                    } else {
+                        Extremum::Minimum
+                    });
+                }
+                Some(Extremum::Maximum) if high.1 - kt >= p => {
+                    extrema.push((high.0, Extremum::Maximum));
+                    seeking = Some(Extremum::Minimum);
+                    low = (i, kt);
+                }
+                Some(Extremum::Minimum) if kt - low.1 >= p => {
+                    extrema.push((low.0, Extremum::Minimum));
+                    seeking = Some(Extremum::Maximum);
+                    high = (i, kt);
+                }
+                _ => {}
+            }
+        }
+        extrema
+    }
+
+    /// A short plain-language briefing on the pass: mission, storm, time window, minimum surface
+    /// pressure and where it was found, peak flight-level and SFMR winds, and whether an eye was
+    /// crossed.
+    pub fn text_summary(&self) -> String {
+        let (mission, storm) = self
+            .mission_id
+            .rsplit_once(' ')
+            .unwrap_or((&self.mission_id, "unnamed storm"));
+        let mut summary = format!("{} into {}", mission, storm);
+        if let (Some(first), Some(last)) = (self.first_obs(), self.last_obs()) {
+            summary += &format!(
+                ", {}-{} UTC {}.",
+                first.time.format("%H:%M"),
+                last.time.format("%H:%M"),
+                self.date.format("%-d %b %Y")
+            );
+        } else {
+            summary += ", no observations.";
+            return summary;
+        }
+
+        match self.min_pressure_obs() {
+            Some(ob) => {
+                let Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(p)) =
+                    ob.surface_pressure
+                else {
+                    unreachable!("min_pressure_obs only returns extrapolated pressures")
+                };
+                summary += &format!(
+                    " Minimum extrapolated surface pressure {:.1} mb at {:.1}{} {:.1}{}.",
+                    p.microbars() as f64 / 1000.0,
+                    ob.location.latitude.angle.decimal_degrees(),
+                    ob.location.latitude.hemisphere.short(),
+                    ob.location.longitude.angle.decimal_degrees(),
+                    ob.location.longitude.hemisphere.short()
+                );
+            }
+            None => summary += " No extrapolated surface pressure reported.",
+        }
+
+        let peak_sfmr = self.obs.iter().filter_map(|ob| ob.peak_sfmr_speed).max();
+        match (
+            self.peak_wind_obs().and_then(|ob| ob.peak_wind_speed),
+            peak_sfmr,
+        ) {
+            (Some(fl), Some(sfmr)) => {
+                summary += &format!(
+                    " Peak flight-level wind {} kt, peak SFMR wind {} kt.",
+                    fl.knots(),
+                    sfmr.knots()
+                )
+            }
+            (Some(fl), None) => {
+                summary += &format!(" Peak flight-level wind {} kt, no SFMR winds.", fl.knots())
+            }
+            (None, Some(sfmr)) => summary += &format!(" Peak SFMR wind {} kt.", sfmr.knots()),
+            (None, None) => summary += " No winds reported.",
+        }
+
+        summary += if self.crosses_eye() {
+            " Eye crossed."
+        } else {
+            " No eye crossing."
+        };
+        summary
+    }
+
+    /// Reduces the pass to at most `target` observations at evenly spaced indices, always keeping
+    /// the minimum-pressure and peak-wind observations so the extremes survive.
+    pub fn downsample(&self, target: usize) -> Vec<&HDHALog> {
+        if target >= self.obs.len() {
+            return self.obs.iter().collect();
+        }
+
+        let index_of = |ob: &HDHALog| self.obs.iter().position(|it| std::ptr::eq(it, ob));
+        let mut keep: BTreeSet<usize> = [self.min_pressure_obs(), self.peak_wind_obs()]
+            .into_iter()
+            .flatten()
+            .filter_map(index_of)
+            .take(target)
+            .collect();
+
+        let last = self.obs.len() - 1;
+        let slots = target - keep.len();
+        for i in 0..slots {
+            if keep.len() == target {
+                break;
+            }
+            let index = if slots == 1 {
+                0
+            } else {
+                i * last / (slots - 1)
+            };
+            keep.insert(index);
+        }
+
+        keep.into_iter().map(|i| &self.obs[i]).collect()
+    }
+
+    /// Rain accumulated along the flight track, in mm: the SFMR rain rate integrated over time
+    /// with the trapezoid rule, using the actual spacing between observations. Intervals where
+    /// either end lacks a rain rate are left out.
+    pub fn estimated_rainfall(&self) -> f64 {
+        self.obs
+            .windows(2)
+            .filter_map(|pair| {
+                let start = pair[0].rain_rate?.mm_per_hr() as f64;
+                let end = pair[1].rain_rate?.mm_per_hr() as f64;
+                let hours = (pair[1].time - pair[0].time).num_milliseconds() as f64 / 3_600_000.0;
+                Some((start + end) / 2.0 * hours)
+            })
+            .sum()
+    }
+
+    /// Pairs of consecutive observations spaced further apart than [`NOMINAL_OBS_INTERVAL`],
+    /// marking where data was dropped.
+    pub fn gaps(&self) -> Vec<(&HDHALog, &HDHALog)> {
+        self.obs
+            .windows(2)
+            .filter(|pair| pair[1].time - pair[0].time > NOMINAL_OBS_INTERVAL)
+            .map(|pair| (&pair[0], &pair[1]))
+            .collect()
+    }
+
+    /// Every run of `n` consecutive observations, for computing rates of change such as
+    /// [`tendency`]. Panics if `n` is zero.
+    pub fn obs_windows(&self, n: usize) -> impl Iterator<Item = &[HDHALog]> {
+        self.obs.windows(n)
+    }
+
+    /// The observations with at least one quality flag set, with their flags, for review.
+    pub fn questionable_obs(&self) -> impl Iterator<Item = (&HDHALog, QualityFlags)> {
+        self.obs
+            .iter()
+            .map(|ob| (ob, ob.quality_flags()))
+            .filter(|(_, flags)| flags.any())
+    }
+
+    /// The fraction of fields across all observations that were reported and not flagged
+    /// questionable, for triaging which passes are worth analyzing; 0 with no observations.
+    ///
+    /// Seven fields of each observation count: the extrapolated surface pressure or D-value
+    /// (flagged by the altitude/pressure flag), temperature and dewpoint (the temperature/dewpoint
+    /// flag), flight-level and peak flight-level wind (the wind flag), and SFMR wind and rain rate
+    /// (the SFMR flag). Time, position, aircraft pressure, and height are always present and the
+    /// optional sea-state groups are rarely sent, so neither counts.
+    pub fn completeness(&self) -> f64 {
+        const FIELDS_PER_OB: usize = 7;
+
+        if self.obs.is_empty() {
+            return 0.0;
+        }
+        let good: usize = self
+            .obs
+            .iter()
+            .map(|ob| {
+                [
+                    ob.surface_pressure.is_some() && !ob.altitude_or_pressure_questionable,
+                    ob.temp.is_some() && !ob.temp_or_dewpoint_questionable,
+                    ob.dewpoint.is_some() && !ob.temp_or_dewpoint_questionable,
+                    ob.wind.is_some() && !ob.winds_questionable,
+                    ob.peak_wind_speed.is_some() && !ob.winds_questionable,
+                    ob.peak_sfmr_speed.is_some() && !ob.sfmr_questionable,
+                    ob.rain_rate.is_some() && !ob.sfmr_questionable,
+                ]
+                .into_iter()
+                .filter(|good| *good)
+                .count()
+            })
+            .sum();
+        good as f64 / (self.obs.len() * FIELDS_PER_OB) as f64
+    }
+
+    /// Combines two bulletins from the same mission into one continuous record, sorted by time.
+    /// Where both carry an observation for the same time, the one from `self` is kept, as is
+    /// `self`'s header, bulletin number and date.
+    pub fn merge(mut self, other: HDOBMessage) -> Result<HDOBMessage, MergeError> {
+        if self.mission_id != other.mission_id {
+            return Err(MergeError::MissionMismatch {
+                left: self.mission_id,
+                right: other.mission_id,
+            });
+        }
+
+        self.obs.extend(other.obs);
+        self.obs.sort_by_key(|ob| ob.time);
+        self.obs.dedup_by_key(|ob| ob.time);
+        Ok(self)
+    }
+
+    /// Counts observations per latitude/longitude grid cell `cell_deg` degrees on a side.
+    ///
+    /// Cells are keyed by `(floor(lat / cell_deg), floor(lon / cell_deg))` in signed decimal
+    /// degrees (north and east positive), so e.g. 93.8°W falls in cell -94 at 1° resolution.
+    pub fn bin_observations(&self, cell_deg: f64) -> HashMap<(i32, i32), usize> {
+        let mut bins = HashMap::new();
+        for ob in &self.obs {
+            let lat = ob.location.latitude.signed_degrees_north();
+            let lon = ob.location.longitude.signed_degrees_east();
+            let cell = (
+                (lat / cell_deg).floor() as i32,
+                (lon / cell_deg).floor() as i32,
+            );
+            *bins.entry(cell).or_insert(0) += 1;
+        }
+        bins
+    }
+
+    /// Estimates conditions at time `t` by linear interpolation between the observations either
+    /// side of it. Returns `None` if `t` falls outside the pass.
+    pub fn interpolate_at(&self, t: DateTime<Utc>) -> Option<InterpolatedObs> {
+        let (first, last) = (self.obs.first()?, self.obs.last()?);
+        if t < first.time || t > last.time {
+            return None;
+        }
+
+        let after = self.obs.partition_point(|ob| ob.time < t);
+        let b = &self.obs[after];
+        if b.time == t || after == 0 {
+            return Some(InterpolatedObs::between(b, b, 0.0));
+        }
+        let a = &self.obs[after - 1];
+        let fraction =
+            (t - a.time).num_milliseconds() as f64 / (b.time - a.time).num_milliseconds() as f64;
+        Some(InterpolatedObs::between(a, b, fraction))
+    }
+
+    /// Samples the pass on a uniform time grid starting at the first observation, `step` apart,
+    /// up to the last observation. If `step` is longer than the pass, the two endpoints are
+    /// returned.
+    pub fn to_uniform_series(&self, step: Duration) -> Vec<InterpolatedObs> {
+        let (first, last) = match (self.obs.first(), self.obs.last()) {
+            (Some(first), Some(last)) if step > Duration::zero() => (first.time, last.time),
+            _ => return vec![],
+        };
+
+        let mut series = vec![];
+        let mut t = first;
+        while t <= last {
+            series.extend(self.interpolate_at(t));
+            t += step;
+        }
+        if series.len() == 1 && first != last {
+            series.extend(self.interpolate_at(last));
+        }
+        series
+    }
+
+    /// A copy of the bulletin with isolated missing temperatures, dewpoints, and extrapolated
+    /// surface pressures (or D-values) filled by linear interpolation in time between the
+    /// observations either side. A value missing from two or more consecutive observations is
+    /// left missing, as is one at either end of the pass. Observations with a filled value have
+    /// [`HDHALog::filled`] set.
+    pub fn fill_missing(&self) -> HDOBMessage {
+        let mut obs = self.obs.clone();
+        for i in 1..obs.len().saturating_sub(1) {
+            let (before, ob, after) = (&self.obs[i - 1], &self.obs[i], &self.obs[i + 1]);
+            let span = (after.time - before.time).num_milliseconds() as f64;
+            if span <= 0.0 {
+                continue;
+            }
+            let fraction = (ob.time - before.time).num_milliseconds() as f64 / span;
+            let lerp = |a: f64, b: f64| a + (b - a) * fraction;
+            let temperature = |get: fn(&HDHALog) -> Option<Temperature>| {
+                let (a, b) = (get(before)?, get(after)?);
+                let mk = lerp(a.millikelvin() as f64, b.millikelvin() as f64);
+                Temperature::try_with_millikelvin(mk.round() as i64)
+            };
+
+            let filled = &mut obs[i];
+            if ob.temp.is_none() {
+                filled.temp = temperature(|ob| ob.temp);
+                filled.filled |= filled.temp.is_some();
+            }
+            if ob.dewpoint.is_none() {
+                filled.dewpoint = temperature(|ob| ob.dewpoint);
+                filled.filled |= filled.dewpoint.is_some();
+            }
+            if ob.surface_pressure.is_none() {
+                use ExtrapolatedSurfacePressure::{DValue as D, ExtrapolatedPressure as Esp};
+                filled.surface_pressure = match (before.surface_pressure, after.surface_pressure) {
+                    (Some(Esp(a)), Some(Esp(b))) => Some(Esp(Pressure::with_microbars(
+                        lerp(a.microbars() as f64, b.microbars() as f64).round() as i32,
+                    ))),
+                    (Some(D(a)), Some(D(b))) => Some(D(DValue::with_meters(
+                        lerp(a.meters_f64(), b.meters_f64()).round() as i32,
+                    ))),
+                    _ => None,
+                };
+                filled.filled |= filled.surface_pressure.is_some();
+            }
+        }
+
+        HDOBMessage {
+            header: self.header.clone(),
+            mission_id: self.mission_id.clone(),
+            obs_number: self.obs_number,
+            date: self.date,
+            obs,
+        }
+    }
+
+    /// A copy of the bulletin with a centered moving average over `window` observations applied
+    /// to the flight-level wind speed, the peak flight-level and SFMR winds, and the
+    /// extrapolated surface pressure, to damp noise before locating centers and wind maxima.
+    ///
+    /// Each value is replaced by the mean of the values present within `window / 2`
+    /// observations either side, so an even `window` behaves as the next odd one. The window
+    /// shrinks at the ends of the pass. Missing values stay missing and are left out of their
+    /// neighbours' averages; D-values, wind directions, positions, and flags are untouched.
+    pub fn smoothed(&self, window: usize) -> HDOBMessage {
+        let half = window / 2;
+        let mean = |i: usize, get: &dyn Fn(&HDHALog) -> Option<f64>| {
+            let range = i.saturating_sub(half)..(i + half + 1).min(self.obs.len());
+            let values: Vec<f64> = self.obs[range].iter().filter_map(get).collect();
+            values.iter().sum::<f64>() / values.len() as f64
+        };
+        let esp = |ob: &HDHALog| match ob.surface_pressure {
+            Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(p)) => {
+                Some(p.microbars() as f64)
+            }
+            _ => None,
+        };
+
+        let mut obs = self.obs.clone();
+        for (i, smoothed) in obs.iter_mut().enumerate() {
+            if let Some(wind) = smoothed.wind.as_mut() {
+                let knots = mean(i, &|ob| ob.wind.map(|w| w.speed.knots_f64()));
+                wind.speed = Speed::with_knots_f64(knots);
+            }
+            if smoothed.peak_wind_speed.is_some() {
+                let knots = mean(i, &|ob| ob.peak_wind_speed.map(|s| s.knots_f64()));
+                smoothed.peak_wind_speed = Some(Speed::with_knots_f64(knots));
+            }
+            if smoothed.peak_sfmr_speed.is_some() {
+                let knots = mean(i, &|ob| ob.peak_sfmr_speed.map(|s| s.knots_f64()));
+                smoothed.peak_sfmr_speed = Some(Speed::with_knots_f64(knots));
+            }
+            if esp(smoothed).is_some() {
+                let microbars = mean(i, &esp).round() as i32;
+                smoothed.surface_pressure =
+                    Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(
+                        Pressure::with_microbars(microbars),
+                    ));
+            }
+        }
+
+        HDOBMessage {
+            header: self.header.clone(),
+            mission_id: self.mission_id.clone(),
+            obs_number: self.obs_number,
+            date: self.date,
+            obs,
+        }
+    }
+
+    /// Converts the observations to an Arrow record batch, as [`to_record_batch`] does.
+    #[cfg(feature = "arrow")]
+    pub fn to_record_batch(&self) -> arrow::record_batch::RecordBatch {
+        to_record_batch(std::slice::from_ref(self))
+    }
+
+    /// Writes the observations as JSON Lines: one object per observation, each on its own line,
+    /// for streaming tools such as `jq`.
+    ///
+    /// Every object repeats the bulletin's `mission_id`, `storm` (the storm name ending the
+    /// mission identifier, if present), and `obs_number`, followed by `time` (RFC 3339),
+    /// `lat`/`lon` (signed degrees, north and east positive), `aircraft_pressure_mb`,
+    /// `height_m`, `surface_pressure_mb` or `dvalue_m`, `temp_c`, `dewpoint_c`, `wind_dir_deg`,
+    /// `wind_speed_kt`, `peak_wind_speed_kt`, `peak_sfmr_speed_kt`, `rain_rate_mm_hr`, and a
+    /// boolean per quality flag: the same names as the `arrow` feature's record batch columns.
+    /// Missing values are `null`, and speeds keep any fraction of a knot. The provisional
+    /// sea-state groups are not included.
+    #[cfg(feature = "json")]
+    pub fn to_jsonl<W: io::Write>(&self, mut w: W) -> io::Result<()> {
+        use serde_json::json;
+
+        let celsius = |t: Temperature| (t.millikelvin() as f64 - 273_150.0) / 1000.0;
+        let mb = |p: Pressure| p.microbars() as f64 / 1000.0;
+        let storm = self.mission_id.split_whitespace().nth(2);
+        for ob in &self.obs {
+            let (surface_pressure, dvalue) = match ob.surface_pressure {
+                Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(p)) => (Some(mb(p)), None),
+                Some(ExtrapolatedSurfacePressure::DValue(d)) => (None, Some(d.meters())),
+                None => (None, None),
+            };
+            let object = json!({
+                "mission_id": self.mission_id,
+                "storm": storm,
+                "obs_number": self.obs_number,
+                "time": ob.time.to_rfc3339(),
+                "lat": ob.location.latitude.signed_degrees_north(),
+                "lon": ob.location.longitude.signed_degrees_east(),
+                "aircraft_pressure_mb": mb(ob.aircraft_pressure),
+                "height_m": ob.height.meters(),
+                "surface_pressure_mb": surface_pressure,
+                "dvalue_m": dvalue,
+                "temp_c": ob.temp.map(celsius),
+                "dewpoint_c": ob.dewpoint.map(celsius),
+                "wind_dir_deg": ob.wind.map(|w| w.direction.angle().decimal_degrees()),
+                "wind_speed_kt": ob.wind.map(|w| w.speed.knots_f64()),
+                "peak_wind_speed_kt": ob.peak_wind_speed.map(|s| s.knots_f64()),
+                "peak_sfmr_speed_kt": ob.peak_sfmr_speed.map(|s| s.knots_f64()),
+                "rain_rate_mm_hr": ob.rain_rate.map(|r| r.mm_per_hr()),
+                "latlon_questionable": ob.latlon_questionable,
+                "altitude_or_pressure_questionable": ob.altitude_or_pressure_questionable,
+                "temp_or_dewpoint_questionable": ob.temp_or_dewpoint_questionable,
+                "winds_questionable": ob.winds_questionable,
+                "sfmr_questionable": ob.sfmr_questionable,
+            });
+            serde_json::to_writer(&mut w, &object)?;
+            w.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_to_jsonl() {
+    let message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt"
+    ))
+    .unwrap();
+    let mut out = vec![];
+    message.to_jsonl(&mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+
+    let lines: Vec<serde_json::Value> = text
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(message.obs.len(), lines.len());
+    let first = &lines[0];
+    assert_eq!("AF309 0112E KAY", first["mission_id"]);
+    assert_eq!("KAY", first["storm"]);
+    assert_eq!(12, first["obs_number"]);
+    assert_eq!(message.obs[0].time.to_rfc3339(), first["time"]);
+    assert_eq!(432, first["dvalue_m"]);
+    assert!(first["surface_pressure_mb"].is_null());
+    assert_eq!(true, first["temp_or_dewpoint_questionable"]);
+}
+
+/// Converts the observations of several bulletins to one Arrow record batch, a row per
+/// observation, for loading into dataframe libraries.
+///
+/// Columns are `mission_id`, `obs_number`, `time` (UTC seconds), `lat`/`lon` (signed degrees,
+/// north and east positive), `aircraft_pressure_mb`, `height_m`, `surface_pressure_mb` or
+/// `dvalue_m` (whichever the observation carries), `temp_c`, `dewpoint_c`, `wind_dir_deg`,
+/// `wind_speed_kt`, `peak_wind_speed_kt`, `peak_sfmr_speed_kt`, `rain_rate_mm_hr`, and a boolean
+/// per quality flag. Missing values are null, and speeds keep any fraction of a knot. The
+/// provisional sea-state groups (wave height and sea surface temperature) are not included.
+#[cfg(feature = "arrow")]
+pub fn to_record_batch(messages: &[HDOBMessage]) -> arrow::record_batch::RecordBatch {
+    use arrow::array::{
+        ArrayRef, BooleanArray, Float64Array, Int32Array, StringArray, TimestampSecondArray,
+        UInt32Array,
+    };
+    use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use arrow::record_batch::RecordBatch;
+    use std::sync::Arc;
+
+    let rows: Vec<(&HDOBMessage, &HDHALog)> = messages
+        .iter()
+        .flat_map(|message| message.obs.iter().map(move |ob| (message, ob)))
+        .collect();
+    let obs = || rows.iter().map(|(_, ob)| ob);
+    let celsius = |t: Temperature| (t.millikelvin() as f64 - 273_150.0) / 1000.0;
+    let float = |name: &str, nullable: bool, f: &dyn Fn(&HDHALog) -> Option<f64>| {
+        let array: ArrayRef = Arc::new(obs().map(|ob| f(ob)).collect::<Float64Array>());
+        (Field::new(name, DataType::Float64, nullable), array)
+    };
+    let uint = |name: &str, f: &dyn Fn(&HDHALog) -> Option<u32>| {
+        let array: ArrayRef = Arc::new(obs().map(|ob| f(ob)).collect::<UInt32Array>());
+        (Field::new(name, DataType::UInt32, true), array)
+    };
+    let flag = |name: &str, f: fn(&HDHALog) -> bool| {
+        let array: ArrayRef = Arc::new(obs().map(|ob| Some(f(ob))).collect::<BooleanArray>());
+        (Field::new(name, DataType::Boolean, false), array)
+    };
+
+    let columns: Vec<(Field, ArrayRef)> = vec![
+        (
+            Field::new("mission_id", DataType::Utf8, false),
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|(message, _)| message.mission_id.as_str()),
+            )),
+        ),
+        (
+            Field::new("obs_number", DataType::UInt32, false),
+            Arc::new(UInt32Array::from_iter_values(
+                rows.iter().map(|(message, _)| message.obs_number),
+            )),
+        ),
+        (
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Second, Some("UTC".into())),
+                false,
+            ),
+            Arc::new(
+                TimestampSecondArray::from_iter_values(obs().map(|ob| ob.time.timestamp()))
+                    .with_timezone("UTC"),
+            ),
+        ),
+        float("lat", false, &|ob| {
+            Some(ob.location.latitude.signed_degrees_north())
+        }),
+        float("lon", false, &|ob| {
+            Some(ob.location.longitude.signed_degrees_east())
+        }),
+        float("aircraft_pressure_mb", false, &|ob| {
+            Some(ob.aircraft_pressure.microbars() as f64 / 1000.0)
+        }),
+        uint("height_m", &|ob| Some(ob.height.meters())),
+        float(
+            "surface_pressure_mb",
+            true,
+            &|ob| match ob.surface_pressure {
+                Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(p)) => {
+                    Some(p.microbars() as f64 / 1000.0)
+                }
+                _ => None,
+            },
+        ),
+        (
+            Field::new("dvalue_m", DataType::Int32, true),
+            Arc::new(
+                obs()
+                    .map(|ob| match ob.surface_pressure {
+                        Some(ExtrapolatedSurfacePressure::DValue(d)) => Some(d.meters()),
+                        _ => None,
+                    })
+                    .collect::<Int32Array>(),
+            ),
+        ),
+        float("temp_c", true, &|ob| ob.temp.map(celsius)),
+        float("dewpoint_c", true, &|ob| ob.dewpoint.map(celsius)),
+        float("wind_dir_deg", true, &|ob| {
+            ob.wind.map(|w| w.direction.angle().decimal_degrees())
+        }),
+        float("wind_speed_kt", true, &|ob| {
+            ob.wind.map(|w| w.speed.knots_f64())
+        }),
+        float("peak_wind_speed_kt", true, &|ob| {
+            ob.peak_wind_speed.map(|s| s.knots_f64())
+        }),
+        float("peak_sfmr_speed_kt", true, &|ob| {
+            ob.peak_sfmr_speed.map(|s| s.knots_f64())
+        }),
+        uint("rain_rate_mm_hr", &|ob| ob.rain_rate.map(|r| r.mm_per_hr())),
+        flag("latlon_questionable", |ob| ob.latlon_questionable),
+        flag("altitude_or_pressure_questionable", |ob| {
+            ob.altitude_or_pressure_questionable
+        }),
+        flag("temp_or_dewpoint_questionable", |ob| {
+            ob.temp_or_dewpoint_questionable
+        }),
+        flag("winds_questionable", |ob| ob.winds_questionable),
+        flag("sfmr_questionable", |ob| ob.sfmr_questionable),
+    ];
+
+    let (fields, arrays): (Vec<Field>, Vec<ArrayRef>) = columns.into_iter().unzip();
+    // Every column has one entry per row and the type declared by its field
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays).unwrap()
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn test_to_record_batch() {
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::{Float64Type, Int32Type};
+
+    let af307 = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    let kay = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt"
+    ))
+    .unwrap();
+
+    let batch = af307.to_record_batch();
+    assert_eq!(20, batch.num_rows());
+    let lat = batch
+        .column_by_name("lat")
+        .unwrap()
+        .as_primitive::<Float64Type>();
+    assert!((lat.value(3) - (20.0 + 6.0 / 60.0)).abs() < 1e-9);
+    let esp = batch
+        .column_by_name("surface_pressure_mb")
+        .unwrap()
+        .as_primitive::<Float64Type>();
+    assert!((esp.value(3) - 1011.5).abs() < 1e-9);
+    let sfmr = batch
+        .column_by_name("peak_sfmr_speed_kt")
+        .unwrap()
+        .as_primitive::<Float64Type>();
+    assert_eq!(21.0, sfmr.value(3));
+    assert!(sfmr.is_null(13));
+
+    let batch = to_record_batch(&[af307, kay]);
+    assert_eq!(40, batch.num_rows());
+    let mission = batch
+        .column_by_name("mission_id")
+        .unwrap()
+        .as_string::<i32>();
+    assert_eq!("AF309 0112E KAY", mission.value(20));
+    let dvalue = batch
+        .column_by_name("dvalue_m")
+        .unwrap()
+        .as_primitive::<Int32Type>();
+    assert!(dvalue.is_null(0));
+    assert_eq!(432, dvalue.value(20));
+    let flags = batch
+        .column_by_name("temp_or_dewpoint_questionable")
+        .unwrap()
+        .as_boolean();
+    assert!(!flags.value(0));
+    assert!(flags.value(20));
+}
+
+#[test]
+fn test_bin_observations() {
+    // KAY spans 25°31'N-26°01'N, 93°48'W-94°22'W
+    let kay = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+    let message = HDOBMessage::parse(kay).unwrap();
+
+    let bins = message.bin_observations(5.0);
+    assert_eq!(1, bins.len());
+    assert_eq!(Some(&20), bins.get(&(5, -19)));
+
+    let bins = message.bin_observations(1.0);
+    assert_eq!(20, bins.values().sum::<usize>());
+    assert!(bins
+        .keys()
+        .all(|(lat, lon)| (25..=26).contains(lat) && (-95..=-94).contains(lon)));
+}
+
+#[test]
+fn test_interpolate_at() {
+    let kay = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+    let message = HDOBMessage::parse(kay).unwrap();
+    let date = NaiveDate::from_ymd_opt(2022, 9, 5).unwrap();
+
+    // 155100 2601N 09348W 3926 07749 0432 -173 //// 222015 015 /// /// 05
+    // 155130 2559N 09349W 3926 07747 0430 -170 //// 221015 016 /// /// 05
+    let attempt = message
+        .interpolate_at(date.and_hms_opt(15, 51, 15).unwrap().and_utc())
+        .unwrap();
+    assert_eq!(Altitude::with_meters(7748), attempt.height);
+    assert_eq!(Some(Temperature::with_millicelsius(-17150)), attempt.temp);
+    assert_eq!(None, attempt.dewpoint);
+    // Speeds keep their fractional knots
+    assert_eq!(Some(Speed::with_knots_f64(15.5)), attempt.peak_wind_speed);
+    assert_eq!(
+        Some(ExtrapolatedSurfacePressure::DValue(DValue::with_meters(
+            431
+        ))),
+        attempt.surface_pressure
+    );
+    assert_eq!(
+        parse_latlon("2600N", "09348W").unwrap().latitude,
+        attempt.location.latitude
+    );
+
+    let exact = message
+        .interpolate_at(date.and_hms_opt(15, 51, 30).unwrap().and_utc())
+        .unwrap();
+    assert_eq!(message.obs[1].location, exact.location);
+
+    assert!(message
+        .interpolate_at(date.and_hms_opt(15, 50, 0).unwrap().and_utc())
+        .is_none());
+    assert!(message
+        .interpolate_at(date.and_hms_opt(16, 1, 0).unwrap().and_utc())
+        .is_none());
+}
+
+#[test]
+fn test_fill_missing() {
+    let bulletin = "URNT15 KNHC 031829
+AF307 0606A EARL               HDOB 15 20220903
+181730 2006N 06139W 9249 00791 0114 +210 +174 116040 041 020 001 00
+181800 2006N 06141W 9247 00793 0115 //// +173 121040 041 021 002 00
+181830 2006N 06143W 9242 00793 0117 +204 +172 120042 043 021 000 00
+181900 2006N 06145W 9240 00794 //// +202 //// 120042 043 021 000 00
+181930 2006N 06147W 9238 00794 //// +201 //// 120042 043 021 000 00
+182000 2006N 06149W 9236 00794 0116 +200 +170 120042 043 021 000 00
+$$";
+    let message = HDOBMessage::parse(bulletin).unwrap();
+    let filled = message.fill_missing();
+
+    assert_eq!(
+        Some(Temperature::with_millicelsius(20_700)),
+        filled.obs[1].temp
+    );
+    assert!(filled.obs[1].filled());
+    assert!(!message.obs[1].filled());
+    // Provenance aside, it is the observation with the gap filled in
+    assert_eq!(
+        HDHALog {
+            temp: filled.obs[1].temp,
+            ..message.obs[1].clone()
+        },
+        filled.obs[1]
+    );
+    assert!(!filled.obs[0].filled());
+    assert!(!filled.obs[2].filled());
+
+    // Two consecutive observations lack surface pressure and dewpoint
+    assert_eq!(None, filled.obs[3].surface_pressure);
+    assert_eq!(None, filled.obs[4].dewpoint);
+    assert!(!filled.obs[3].filled());
+    assert_eq!(message.obs.len(), filled.obs.len());
+}
+
+#[test]
+fn test_to_uniform_series() {
+    let kay = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+    let mut message = HDOBMessage::parse(kay).unwrap();
+    message.obs.truncate(7); // 155100 to 155400
+
+    let series = message.to_uniform_series(Duration::minutes(1));
+    assert_eq!(4, series.len());
+    assert_eq!(message.obs[0].time, series[0].time);
+    assert_eq!(message.obs[6].time, series[3].time);
+    assert_eq!(
+        message.obs[2].aircraft_pressure,
+        series[1].aircraft_pressure
+    );
+
+    let series = message.to_uniform_series(Duration::minutes(10));
+    assert_eq!(2, series.len());
+    assert_eq!(message.obs[6].time, series[1].time);
+
+    assert!(message.to_uniform_series(Duration::zero()).is_empty());
+}
+
+#[test]
+fn test_from_obs() {
+    let kay = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt"
+    ))
+    .unwrap();
+    let built = HDOBMessage::from_obs(
+        kay.header.clone(),
+        kay.mission_id.clone(),
+        kay.obs_number,
+        kay.date,
+        kay.obs[5..10].to_vec(),
+    )
+    .unwrap();
+    assert_eq!(kay.mission_id, built.mission_id);
+    assert_eq!(&kay.obs[5..10], &built.obs[..]);
+
+    let mut obs = kay.obs[5..10].to_vec();
+    obs.swap(2, 3);
+    assert_eq!(
+        Err(BuildError::Unsorted { index: 3 }),
+        HDOBMessage::from_obs(kay.header, kay.mission_id, 12, kay.date, obs).map(|_| ())
+    );
+    let error: Box<dyn Error + Send + Sync> = Box::new(BuildError::Unsorted { index: 3 });
+    assert_eq!(
+        "observation 3 is earlier than the one before it",
+        error.to_string()
+    );
+}
+
+#[test]
+fn test_estimated_rainfall() {
+    let mut message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    // Two minutes of 60 mm/hr rain, then none reported
+    for (i, ob) in message.obs.iter_mut().enumerate() {
+        ob.rain_rate = (i <= 4).then(|| RainRate::with_mm_per_hr(60));
+    }
+    assert!((message.estimated_rainfall() - 2.0).abs() < 1e-9);
+
+    message.obs.truncate(1);
+    assert_eq!(0.0, message.estimated_rainfall());
+}
+
+#[test]
+fn test_gaps() {
+    let mut message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt"
+    ))
+    .unwrap();
+    assert!(message.gaps().is_empty());
+
+    let removed = message.obs.remove(5);
+    let gaps = message.gaps();
+    assert_eq!(1, gaps.len());
+    assert_eq!(removed.time - NOMINAL_OBS_INTERVAL, gaps[0].0.time);
+    assert_eq!(removed.time + NOMINAL_OBS_INTERVAL, gaps[0].1.time);
+}
+
+#[test]
+fn test_obs_windows() {
+    let mut message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    message.obs.drain(..2);
+    message.obs.truncate(3);
+    let windows: Vec<&[HDHALog]> = message.obs_windows(2).collect();
+    assert_eq!(2, windows.len());
+    assert_eq!(&message.obs[1..3], windows[1]);
+
+    // 1011.4 mb to 1011.9 mb in one minute
+    let rate = tendency(&message.obs).unwrap();
+    assert!((rate - 30.0).abs() < 1e-9, "{}", rate);
+    assert_eq!(None, tendency(&message.obs[..1]));
+    assert_eq!(None, tendency(&[]));
+}
+
+#[test]
+fn test_questionable_obs() {
+    let message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    let sfmr_only = QualityFlags {
+        sfmr_questionable: true,
+        ..Default::default()
+    };
+    let questionable: Vec<_> = message.questionable_obs().collect();
+    assert_eq!(
+        vec![
+            (&message.obs[13], sfmr_only),
+            (&message.obs[14], sfmr_only),
+            (&message.obs[15], sfmr_only),
+        ],
+        questionable
+    );
+}
+
+#[test]
+fn test_completeness() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+    let obs: Vec<HDHALog> = (0..10)
+        .map(|i| {
+            let line = format!(
+                "18{:02}00 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00",
+                i
+            );
+            HDHALog::parse(&date, &line).unwrap()
+        })
+        .collect();
+    let mut message = HDOBMessageBuilder::new("AF307 0606A EARL")
+        .obs(obs)
+        .build()
+        .unwrap();
+    assert_eq!(1.0, message.completeness());
+
+    for ob in message.obs.iter_mut().step_by(2) {
+        ob.wind = None;
+    }
+    assert_eq!(1.0 - 0.5 / 7.0, message.completeness());
+
+    // SFMR and rain rate questionable on three of twenty observations
+    let af307 = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    assert_eq!(1.0 - 6.0 / 140.0, af307.completeness());
+
+    message.obs.clear();
+    assert_eq!(0.0, message.completeness());
+}
+
+#[test]
+fn test_smoothed() {
+    let mut message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    // Steady 40 kt and 1010 mb, but for a 100 kt, 990 mb spike mid-pass
+    for (i, ob) in message.obs.iter_mut().enumerate() {
+        let (knots, mb) = if i == 10 { (100, 990) } else { (40, 1010) };
+        ob.wind = Some(Wind::from_degrees_knots(120.0, knots));
+        ob.surface_pressure = Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(
+            Pressure::with_microbars(mb * 1000),
+        ));
+    }
+    message.obs[0].wind = None;
+
+    let smoothed = message.smoothed(5);
+    let knots: Vec<f64> = smoothed
+        .obs
+        .iter()
+        .filter_map(|ob| ob.wind.map(|w| w.speed.knots_f64()))
+        .collect();
+    let mb: Vec<f64> = smoothed
+        .obs
+        .iter()
+        .filter_map(|ob| ob.surface_pressure().map(|p| p.microbars() as f64 / 1000.0))
+        .collect();
+    // The spike is spread evenly over the five observations around it
+    assert_eq!(vec![40.0; 7], knots[..7]);
+    assert_eq!(vec![52.0; 5], knots[7..12]);
+    assert_eq!(vec![1006.0; 5], mb[8..13]);
+    let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+    assert!((mean(&knots) - (40.0 + 60.0 / 19.0)).abs() < 1e-9);
+    assert!((mean(&mb) - 1009.0).abs() < 1e-9);
+
+    assert_eq!(None, smoothed.obs[0].wind);
+    assert_eq!(message.obs[5].location, smoothed.obs[5].location);
+    assert_eq!(
+        message.obs[13].quality_flags(),
+        smoothed.obs[13].quality_flags()
+    );
+    assert_eq!(
+        message.obs[13].peak_sfmr_speed,
+        smoothed.obs[13].peak_sfmr_speed
+    );
+
+    // A broad 60 kt maximum early in the pass and a one-observation 100 kt spike later
+    for (i, ob) in message.obs.iter_mut().enumerate() {
+        let knots = match i {
+            2..=6 => 60,
+            15 => 100,
+            _ => 40,
+        };
+        ob.peak_wind_speed = Some(Speed::with_knots(knots));
+    }
+    let center = message.obs[0].location;
+    let distance = |i: usize| Some(center.distance_to(&message.obs[i].location));
+    assert_eq!(
+        distance(15),
+        message.radius_of_max_wind(&center, WindSource::FlightLevel)
+    );
+    assert_eq!(
+        distance(4),
+        message
+            .smoothed(5)
+            .radius_of_max_wind(&center, WindSource::FlightLevel)
+    );
+}
+
+#[test]
+fn test_obs_nearest() {
+    let message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    let at = |hhmmss: u32| {
+        let (h, m, s) = (hhmmss / 10000, hhmmss / 100 % 100, hhmmss % 100);
+        message.date.and_hms_opt(h, m, s).unwrap().and_utc()
+    };
+    // Observations every 30 seconds from 18:17:00
+    assert_eq!(Some(&message.obs[2]), message.obs_nearest(at(181800)));
+    assert_eq!(Some(&message.obs[2]), message.obs_nearest(at(181810)));
+    assert_eq!(Some(&message.obs[3]), message.obs_nearest(at(181820)));
+    assert_eq!(Some(&message.obs[2]), message.obs_nearest(at(181815)));
+    assert_eq!(Some(&message.obs[0]), message.obs_nearest(at(170000)));
+    assert_eq!(Some(&message.obs[19]), message.obs_nearest(at(235959)));
+
+    let empty = HDOBMessage {
+        obs: vec![],
+        ..message
+    };
+    assert_eq!(None, empty.obs_nearest(Utc::now()));
+}
+
+#[test]
+fn test_obs_at() {
+    let message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    assert_eq!(Some(&message.obs[3]), message.obs_at(3));
+    assert_eq!(Some(&message.obs[0]), message.first_obs());
+    assert_eq!(Some(&message.obs[19]), message.last_obs());
+    assert_eq!(None, message.obs_at(20));
+
+    let empty = HDOBMessage {
+        obs: vec![],
+        ..message
+    };
+    assert_eq!(None, empty.obs_at(0));
+    assert_eq!(None, empty.first_obs());
+    assert_eq!(None, empty.last_obs());
+}
+
+#[test]
+fn test_segments_by_level() {
+    let mut message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    // The aircraft holds near 925 mb throughout
+    assert_eq!(1, message.segments_by_level(5.0).len());
+
+    // Step up to 850 mb after the tenth observation
+    for ob in message.obs[10..].iter_mut() {
+        ob.aircraft_pressure = Pressure::with_microbars(850_000);
+    }
+    let segments = message.segments_by_level(5.0);
+    assert_eq!(2, segments.len());
+    assert_eq!(&message.obs[..10], segments[0]);
+    assert_eq!(&message.obs[10..], segments[1]);
+
+    // Climb gradually to 700 mb instead
+    for (i, ob) in message.obs[10..].iter_mut().enumerate() {
+        let mb = (925 - 40 * (i as i32 + 1)).max(700);
+        ob.aircraft_pressure = Pressure::with_microbars(mb * 1000);
+    }
+    let segments = message.segments_by_level(5.0);
+    assert_eq!(3, segments.len());
+    assert_eq!(&message.obs[..10], segments[0]);
+    assert_eq!(&message.obs[10..15], segments[1]);
+    assert_eq!(&message.obs[15..], segments[2]);
+
+    let empty = HDOBMessage {
+        obs: vec![],
+        ..message
+    };
+    assert!(empty.segments_by_level(5.0).is_empty());
+}
+
+#[test]
+fn test_vertical_legs() {
+    let mut message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    assert_eq!(
+        vec![VerticalMode::Level],
+        message
+            .vertical_legs()
+            .iter()
+            .map(|(mode, _)| *mode)
+            .collect::<Vec<_>>()
+    );
+
+    // Hold 700 mb, descend 40 mb each observation, then hold 925 mb
+    for (i, ob) in message.obs.iter_mut().enumerate() {
+        let mb = (700 + 40 * (i as i32 - 4).max(0)).min(925);
+        ob.aircraft_pressure = Pressure::with_microbars(mb * 1000);
+    }
+    let legs = message.vertical_legs();
+    assert_eq!(3, legs.len());
+    assert_eq!((VerticalMode::Level, &message.obs[..5]), legs[0]);
+    assert_eq!((VerticalMode::Descending, &message.obs[5..10]), legs[1]);
+    assert_eq!((VerticalMode::Level, &message.obs[10..]), legs[2]);
+
+    // And back up again
+    message.obs.reverse();
+    let modes: Vec<_> = message.vertical_legs().iter().map(|(m, _)| *m).collect();
+    assert_eq!(
+        vec![
+            VerticalMode::Level,
+            VerticalMode::Ascending,
+            VerticalMode::Level
+        ],
+        modes
+    );
+}
+
+#[test]
+fn test_wind_extrema() {
+    let mut message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    // Two eyewalls around an eye, with some noise on each
+    let speeds = [
+        20, 35, 33, 50, 70, 64, 72, 45, 30, 15, 18, 12, 40, 65, 80, 60, 40, 42, 30, 20,
+    ];
+    for (ob, kt) in message.obs.iter_mut().zip(speeds) {
+        ob.wind = Some(Wind::from_degrees_knots(90.0, kt));
+    }
+    message.obs[1].wind = None;
+
+    assert_eq!(
+        vec![
+            (6, Extremum::Maximum),
+            (11, Extremum::Minimum),
+            (14, Extremum::Maximum),
+        ],
+        message.wind_extrema(Speed::with_knots(10))
+    );
+    // Without a threshold, every wiggle counts
+    assert_eq!(9, message.wind_extrema(Speed::with_knots(1)).len());
+    assert!(message.wind_extrema(Speed::with_knots(100)).is_empty());
+}
+
+#[test]
+fn test_observation_key() {
+    let earl = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220905-09-HDOB-EARL-1006A-AF308.txt"
+    ))
+    .unwrap();
+    let ob = &earl.obs[0];
+    let key = earl.observation_key(ob);
+    assert_eq!(
+        format!("AF308 1006A EARL/{}/09", ob.time.format("%Y%m%dT%H%M%SZ")),
+        key.to_string()
+    );
+
+    // Another aircraft reporting in the same second
+    let other = HDOBMessage {
+        mission_id: "NOAA2 0906A EARL".to_string(),
+        obs: vec![],
+        ..earl
+    };
+    let other_key = other.observation_key(ob);
+    assert_ne!(key, other_key);
+    assert_ne!(key.to_string(), other_key.to_string());
+    assert!(key < other_key);
+    assert!(key.to_string() < other_key.to_string());
+}
+
+#[test]
+fn test_circular_stats() {
+    let mut message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+
+    // Steady southeasterlies of 113-123°
+    let (direction, std) = message.circular_stats().unwrap();
+    assert!((direction.angle().decimal_degrees() - 119.0).abs() < 1.0);
+    assert!(std < 5.0);
+    let mean = message.mean_wind().unwrap();
+    assert!((mean.direction.angle().decimal_degrees() - 119.0).abs() < 1.0);
+    assert!((38..=41).contains(&mean.speed.knots()));
+
+    // Either side of north, which a naive average of 350° and 10° would put at 180°
+    for (i, ob) in message.obs.iter_mut().enumerate() {
+        let degrees = if i % 2 == 0 { 350.0 } else { 10.0 };
+        ob.wind = Some(Wind::from_degrees_knots(degrees, 20));
+    }
+    let (direction, std) = message.circular_stats().unwrap();
+    assert_eq!(crate::measure::NORTH, direction);
+    assert!(std < 15.0);
+
+    // Scattered all around the compass
+    for (i, ob) in message.obs.iter_mut().enumerate() {
+        ob.wind = Some(Wind::from_degrees_knots(i as f64 * 137.0, 20));
+    }
+    let (_, std) = message.circular_stats().unwrap();
+    assert!(std > 90.0);
+
+    for ob in message.obs.iter_mut() {
+        ob.wind = None;
+    }
+    assert_eq!(None, message.circular_stats());
+    assert_eq!(None, message.mean_wind());
+}
+
+#[test]
+fn test_level_shear() {
+    let mut message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    // A single level
+    assert_eq!(None, message.level_shear());
+
+    // Southerlies of 10 kt at 850 mb under westerlies of 20 kt at 700 mb
+    for (i, ob) in message.obs.iter_mut().enumerate() {
+        let (mb, wind) = if i < 10 {
+            (850, Wind::from_degrees_knots(180.0, 10))
+        } else {
+            (700, Wind::from_degrees_knots(270.0, 20))
+        };
+        ob.aircraft_pressure = Pressure::with_microbars(mb * 1000);
+        ob.wind = Some(wind);
+    }
+    let shear = message.level_shear().unwrap();
+    // (-20, 0) - (0, -10) = (-20, 10) kt, from 296.6°
+    assert!((shear.direction.angle().decimal_degrees() - 296.57).abs() < 0.01);
+    assert_eq!(Speed::with_knots_f64(500f64.sqrt()), shear.speed);
+
+    for ob in message.obs[10..].iter_mut() {
+        ob.wind = None;
+    }
+    assert_eq!(None, message.level_shear());
+}
+
+#[test]
+fn test_data_source() {
+    let noaa = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220905-31-HDOB-EARL-0906A-NOAA2.txt"
+    ))
+    .unwrap();
+    assert_eq!(Some(DataSource::Noaa), noaa.data_source());
+
+    let af = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220905-09-HDOB-EARL-1006A-AF308.txt"
+    ))
+    .unwrap();
+    assert_eq!(Some(DataSource::AirForceReserve), af.data_source());
+
+    let unknown = HDOBMessage {
+        mission_id: "XX123 0101A TEST".to_string(),
+        ..af
+    };
+    assert_eq!(None, unknown.data_source());
+}
+
+#[test]
+fn test_transmission_delay() {
+    let kay = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt"
+    ))
+    .unwrap();
+    assert_eq!(Some("KNHC"), kay.issuing_center());
+    let issued = NaiveDate::from_ymd_opt(2022, 9, 5)
+        .unwrap()
+        .and_hms_opt(16, 6, 0)
+        .unwrap()
+        .and_utc();
+    assert_eq!(Some(issued), kay.issuance_time());
+    // Last observation at 16:00:30
+    assert_eq!(
+        Some(Duration::minutes(5) + Duration::seconds(30)),
+        kay.transmission_delay()
+    );
+
+    // Observed late on the 30th, issued early on the 1st
+    let date = NaiveDate::from_ymd_opt(2022, 9, 30).unwrap();
+    let ob = HDHALog::parse(
+        &date,
+        "235830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00",
+    )
+    .unwrap();
+    let rollover = HDOBMessage {
+        header: "URNT15 KNHC 010003".to_string(),
+        date,
+        obs: vec![ob],
+        ..kay
+    };
+    assert_eq!(
+        Some(Duration::minutes(4) + Duration::seconds(30)),
+        rollover.transmission_delay()
+    );
+
+    let unheaded = HDOBMessage {
+        header: "URNT15 KNHC".to_string(),
+        ..rollover
+    };
+    assert_eq!(None, unheaded.transmission_delay());
+}
+
+#[test]
+fn test_wind_center() {
+    let mut message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    // Eyewalls at 6 and 14 around a pressure minimum at 10, with the calm a little behind it
+    let speeds = [
+        40, 45, 50, 60, 70, 80, 90, 60, 30, 15, 10, 5, 30, 70, 85, 75, 60, 50, 45, 40,
+    ];
+    for (ob, kt) in message.obs.iter_mut().zip(speeds) {
+        ob.wind = Some(Wind::from_degrees_knots(90.0, kt));
+    }
+    message.obs[10].surface_pressure = Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(
+        Pressure::with_microbars(990_000),
+    ));
+    assert!(std::ptr::eq(
+        &message.obs[11],
+        message.wind_center().unwrap()
+    ));
+
+    // The calmest point outside the eyewalls is ignored
+    message.obs[19].wind = Some(Wind::from_degrees_knots(90.0, 2));
+    assert!(std::ptr::eq(
+        &message.obs[11],
+        message.wind_center().unwrap()
+    ));
+
+    // KAY reports D-values; its lightest wind near the lowest one lies within a few miles
+    let kay = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt"
+    ))
+    .unwrap();
+    let calm = kay.wind_center().unwrap();
+    assert!(calm.location.approx_eq(&kay.obs[1].location, 10.0));
+    assert_eq!(Speed::with_knots(14), calm.wind.unwrap().speed);
+
+    let empty = HDOBMessage { obs: vec![], ..kay };
+    assert_eq!(None, empty.wind_center());
+}
+
+#[test]
+fn test_center_fix_confidence() {
+    let mut message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    // Pressure rises steadily from the first observation under steady southeasterlies
+    let shoulder = message.estimate_center().unwrap();
+    assert_eq!(message.obs[0].time, shoulder.time);
+    assert_eq!(Pressure::with_microbars(1_011_400), shoulder.min_pressure);
+    assert_eq!(Some(Speed::with_knots(42)), shoulder.max_wind);
+    assert!(shoulder.confidence < 0.1, "{}", shoulder.confidence);
+
+    // A V-shaped pressure trace with the wind reversing across it
+    for (i, ob) in message.obs.iter_mut().enumerate() {
+        let mb = 980 + (i as i32 - 10).abs();
+        ob.surface_pressure = Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(
+            Pressure::with_microbars(mb * 1000),
+        ));
+        let degrees = if i < 10 { 90.0 } else { 270.0 };
+        ob.wind = Some(Wind::from_degrees_knots(degrees, 60));
+    }
+    let crossing = message.estimate_center().unwrap();
+    assert_eq!(message.obs[10].time, crossing.time);
+    assert_eq!(FixMethod::MinimumPressure, crossing.method);
+    assert!(crossing.confidence > 0.9, "{}", crossing.confidence);
+    assert!(crossing.confidence > shoulder.confidence);
+}
+
+#[test]
+fn test_radius_of_max_wind() {
+    let message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    let center = message.estimate_center().unwrap().location;
+    let peak = message.peak_wind_obs().unwrap();
+    let rmw = message
+        .radius_of_max_wind(&center, WindSource::FlightLevel)
+        .unwrap();
+    assert_eq!(center.distance_to(&peak.location), rmw);
+    assert!(rmw > 0.0 && rmw < 50.0, "{}", rmw);
+
+    let sfmr = message
+        .radius_of_max_wind(&center, WindSource::Sfmr)
+        .unwrap();
+    assert!(sfmr > 0.0 && sfmr < 50.0, "{}", sfmr);
+
+    // No SFMR at 392 mb
+    let kay = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt"
+    ))
+    .unwrap();
+    assert_eq!(None, kay.radius_of_max_wind(&center, WindSource::Sfmr));
+}
+
+#[test]
+fn test_pressure_gradient() {
+    let message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    // Pressure rises from 1011.4 mb at the estimated center to 1012.7 mb along the leg
+    let center = message.estimate_center().unwrap().location;
+    let gradient = message.pressure_gradient(&center).unwrap();
+    assert!(gradient < 0.0 && gradient > -1.0, "{}", gradient);
+
+    // Nothing within 30 nm of a point 10° away
+    let far = Coordinate {
+        latitude: Latitude {
+            angle: Angle::with_decimal_degrees(30.0),
+            hemisphere: NORTH,
+        },
+        ..center
+    };
+    assert_eq!(None, message.pressure_gradient(&far));
+}
+
+#[test]
+fn test_storm_motion() {
+    let pass = |hour: u32, lat: &str| {
+        let kay = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+        let mut message = HDOBMessage::parse(kay).unwrap();
+        let date = message.date;
+        message.obs = vec![HDHALog::parse(
+            &date,
+            &format!(
+                "{:02}0000 {}N 06000W 9236 00794 9800 +201 +173 123041 041 021 002 00",
+                hour, lat
+            ),
+        )
+        .unwrap()];
+        message
+    };
+
+    // One degree (60 nm) due north in three hours
+    let earlier = pass(12, "2000");
+    let later = pass(15, "2100");
+    assert_eq!(
+        Some(CenterFix {
+            time: later.obs[0].time,
+            location: later.obs[0].location,
+            confidence: 0.0,
+            method: FixMethod::MinimumPressure,
+            min_pressure: Pressure::with_microbars(980_000),
+            max_wind: Some(Speed::with_knots(41)),
+        }),
+        later.estimate_center()
+    );
+    let motion = storm_motion(&earlier, &later).unwrap();
+    assert_eq!(crate::measure::NORTH, motion.direction);
+    assert_eq!(Speed::with_knots(20), motion.speed);
+    assert_eq!(Some(motion), storm_motion(&later, &earlier));
+
+    assert_eq!(None, storm_motion(&earlier, &earlier));
+    let high = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt"
+    ))
+    .unwrap();
+    assert_eq!(None, storm_motion(&earlier, &high));
+}
+
+#[test]
+fn test_text_summary() {
+    let message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    assert_eq!(
+        "AF307 0606A into EARL, 18:17-18:26 UTC 3 Sep 2022. \
+         Minimum extrapolated surface pressure 1011.4 mb at 20.0N 61.7W. \
+         Peak flight-level wind 42 kt, peak SFMR wind 23 kt. No eye crossing.",
+        message.text_summary()
+    );
+}
+
+#[test]
+fn test_crosses_eye() {
+    let mut message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    assert!(!message.crosses_eye());
+
+    // Calm, low-pressure center in the middle of the pass
+    let center = &mut message.obs[10];
+    center.surface_pressure = Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(
+        Pressure::with_microbars(990_000),
+    ));
+    center.wind = Some(Wind::from_degrees_knots(0.0, 5));
+    assert!(message.crosses_eye());
+}
+
+#[test]
+fn test_merge() {
+    let earl = include_str!("../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt");
+    let total = HDOBMessage::parse(earl).unwrap().obs.len();
+
+    // Split one bulletin into two overlapping ones, presented out of order
+    let mut first = HDOBMessage::parse(earl).unwrap();
+    first.obs.truncate(12);
+    let mut second = HDOBMessage::parse(earl).unwrap();
+    second.obs_number += 1;
+    second.obs.drain(..8);
+
+    let merged = second.merge(first).unwrap();
+    assert_eq!(total, merged.obs.len());
+    assert!(merged.obs.windows(2).all(|w| w[0].time < w[1].time));
+
+    let other = include_str!("../testdata/hdob/20220905-09-HDOB-EARL-1006A-AF308.txt");
+    let attempt = HDOBMessage::parse(earl)
+        .unwrap()
+        .merge(HDOBMessage::parse(other).unwrap());
+    assert_eq!(
+        Err(MergeError::MissionMismatch {
+            left: "AF307 0606A EARL".to_string(),
+            right: "AF308 1006A EARL".to_string(),
+        }),
+        attempt.map(|_| ())
+    );
+}
+
+#[test]
+fn test_downsample() {
+    let earl = include_str!("../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt");
+    let message = HDOBMessage::parse(earl).unwrap();
+    let min_pressure = message.min_pressure_obs().unwrap();
+
+    for target in [1, 2, 5, 10] {
+        let attempt = message.downsample(target);
+        assert!(attempt.len() <= target);
+        assert!(attempt.iter().any(|ob| std::ptr::eq(*ob, min_pressure)));
+    }
+
+    assert_eq!(0, message.downsample(0).len());
+    assert_eq!(message.obs.len(), message.downsample(100).len());
+}
+
+/// Assembles an [`HDOBMessage`] from observations in any order, such as synthetic ones for
+/// testing analyses, filling in whatever metadata isn't given.
+///
+/// ```
+/// use libcyclone::recon::{EncodeOptions, HDHALog, HDOBMessage, HDOBMessageBuilder};
+///
+/// let date = chrono::NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+/// let line = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00";
+/// let message = HDOBMessageBuilder::new("AF307 0606A EARL")
+///     .obs_number(15)
+///     .ob(HDHALog::parse(&date, line).unwrap())
+///     .build()
+///     .unwrap();
+/// let text = message.to_bulletin(&EncodeOptions::default()).unwrap();
+/// assert!(text.starts_with("URNT15 KNHC 031818\nAF307 0606A EARL"));
+/// assert_eq!(1, HDOBMessage::parse(&text).unwrap().obs.len());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HDOBMessageBuilder {
+    header: Option<String>,
+    mission_id: String,
+    obs_number: u32,
+    date: Option<NaiveDate>,
+    obs: Vec<HDHALog>,
+}
+
+impl HDOBMessageBuilder {
+    pub fn new(mission_id: impl Into<String>) -> Self {
+        Self {
+            mission_id: mission_id.into(),
+            obs_number: 1,
+            ..Default::default()
+        }
+    }
+
+    /// The WMO header line. Defaults to `URNT15 KNHC` with the time of the last observation.
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.header = Some(header.into());
+        self
+    }
+
+    /// The bulletin number. Defaults to 1.
+    pub fn obs_number(mut self, obs_number: u32) -> Self {
+        self.obs_number = obs_number;
+        self
+    }
+
+    /// The mission header date. Defaults to the date of the first observation.
+    pub fn date(mut self, date: NaiveDate) -> Self {
+        self.date = Some(date);
+        self
+    }
+
+    pub fn ob(mut self, ob: HDHALog) -> Self {
+        self.obs.push(ob);
+        self
+    }
+
+    pub fn obs(mut self, obs: impl IntoIterator<Item = HDHALog>) -> Self {
+        self.obs.extend(obs);
+        self
+    }
+
+    /// Sorts the observations by time and builds the message, failing with
+    /// [`BuildError::Empty`] if there are none.
+    pub fn build(mut self) -> Result<HDOBMessage, BuildError> {
+        self.obs.sort_by_key(|ob| ob.time);
+        let (first, last) = match (self.obs.first(), self.obs.last()) {
+            (Some(first), Some(last)) => (first.time, last.time),
+            _ => return Err(BuildError::Empty),
+        };
+        let header = self
+            .header
+            .unwrap_or_else(|| format!("URNT15 KNHC {}", last.format("%d%H%M")));
+        let date = self.date.unwrap_or(first.date_naive());
+        HDOBMessage::from_obs(header, self.mission_id, self.obs_number, date, self.obs)
+    }
+}
+
+#[test]
+fn test_hdob_message_builder() {
+    let af307 = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    let mut shuffled = af307.obs.clone();
+    shuffled.reverse();
+    let message = HDOBMessageBuilder::new("AF307 0606A EARL")
+        .obs_number(15)
+        .obs(shuffled)
+        .build()
+        .unwrap();
+    assert_eq!("URNT15 KNHC 031826", message.header);
+    assert_eq!(af307.date, message.date);
+    assert_eq!(af307.obs, message.obs);
+
+    let text = message.to_bulletin(&EncodeOptions::default()).unwrap();
+    let original = include_str!("../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt");
+    for line in text.lines().skip(2).take(af307.obs.len()) {
+        assert!(original.contains(line), "{}", line);
+    }
+    let reparsed = HDOBMessage::parse(&text).unwrap();
+    assert_eq!(af307.mission_id, reparsed.mission_id);
+    assert_eq!(af307.obs_number, reparsed.obs_number);
+    assert_eq!(af307.obs, reparsed.obs);
+
+    assert_eq!(
+        Some(BuildError::Empty),
+        HDOBMessageBuilder::new("AF307 0606A EARL").build().err()
+    );
+}
+
+#[test]
+fn test_to_bulletin_strict_width() {
+    let strict = EncodeOptions {
+        strict_width: true,
+        ..Default::default()
+    };
+    for original in [
+        include_str!("../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"),
+        include_str!("../testdata/hdob/20220905-09-HDOB-EARL-1006A-AF308.txt"),
+        include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt"),
+    ] {
+        // From the WMO header through $$, without the sequence number and trailing ;
+        let start = original.find("URNT15").unwrap();
+        let end = original.find("$$").unwrap() + "$$\n".len();
+        let message = HDOBMessage::parse(original).unwrap();
+        assert_eq!(&original[start..end], message.to_bulletin(&strict).unwrap());
+    }
+
+    let mut message = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt"
+    ))
+    .unwrap();
+    message.obs[3].height = Altitude::with_meters(123_456);
+    assert_eq!(
+        Err(EncodeError::TooWide {
+            field: "height",
+            text: "123456".to_string()
+        }),
+        message.to_bulletin(&strict)
+    );
+    assert!(message.to_bulletin(&EncodeOptions::default()).is_ok());
+
+    message.obs_number = 100;
+    assert_eq!(
+        Some(EncodeError::TooWide {
+            field: "obs number",
+            text: "100".to_string()
+        }),
+        message.to_bulletin(&strict).err()
+    );
+    message.mission_id = "AF309 0112E KAY".to_string() + &" ".repeat(20);
+    assert_eq!(
+        Some(EncodeError::TooWide {
+            field: "mission id",
+            text: message.mission_id.clone()
+        }),
+        message.to_bulletin(&strict).err()
+    );
+    let error: Box<dyn Error + Send + Sync> = Box::new(message.to_bulletin(&strict).unwrap_err());
+    assert_eq!(
+        format!(
+            "mission id too wide for its fixed width: {:?}",
+            message.mission_id
+        ),
+        error.to_string()
+    );
+}
+
+/// The bulletins of one mission, in time order, for analysis across the whole flight.
+#[derive(Debug)]
+pub struct MissionSession {
+    mission_id: String,
+    messages: Vec<HDOBMessage>,
+}
+
+impl MissionSession {
+    pub fn new(mission_id: String) -> Self {
+        Self {
+            mission_id,
+            messages: vec![],
+        }
+    }
+
+    /// Starts a session from a set of bulletins in any order, which must all share a mission.
+    pub fn from_messages(
+        messages: impl IntoIterator<Item = HDOBMessage>,
+    ) -> Result<Option<Self>, MergeError> {
+        let mut messages = messages.into_iter();
+        let Some(first) = messages.next() else {
+            return Ok(None);
+        };
+        let mut session = Self::new(first.mission_id.clone());
+        session.add(first)?;
+        for message in messages {
+            session.add(message)?;
+        }
+        Ok(Some(session))
+    }
+
+    /// Adds a bulletin, keeping the session ordered by first observation time.
+    pub fn add(&mut self, message: HDOBMessage) -> Result<(), MergeError> {
+        if message.mission_id != self.mission_id {
+            return Err(MergeError::MissionMismatch {
+                left: self.mission_id.clone(),
+                right: message.mission_id,
+            });
+        }
+        let start = |m: &HDOBMessage| m.first_obs().map(|ob| ob.time);
+        let index = self
+            .messages
+            .partition_point(|it| start(it) <= start(&message));
+        self.messages.insert(index, message);
+        Ok(())
+    }
+
+    pub fn mission_id(&self) -> &str {
+        &self.mission_id
+    }
+
+    pub fn messages(&self) -> &[HDOBMessage] {
+        &self.messages
+    }
+
+    /// The observation with the lowest extrapolated surface pressure across every bulletin.
+    pub fn min_pressure_over_session(&self) -> Option<&HDHALog> {
+        self.messages
+            .iter()
+            .filter_map(HDOBMessage::min_pressure_obs)
+            .min_by_key(|ob| match ob.surface_pressure {
+                Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(p)) => p,
+                _ => unreachable!("min_pressure_obs only returns extrapolated pressures"),
+            })
+    }
+
+    /// The observation with the highest peak flight-level wind across every bulletin.
+    pub fn peak_winds_over_session(&self) -> Option<&HDHALog> {
+        self.messages
+            .iter()
+            .filter_map(HDOBMessage::peak_wind_obs)
+            .max_by_key(|ob| ob.peak_wind_speed)
+    }
+
+    /// The center estimated from each bulletin that has one, in time order.
+    pub fn center_track(&self) -> Vec<CenterFix> {
+        self.messages
+            .iter()
+            .filter_map(HDOBMessage::estimate_center)
+            .collect()
+    }
+}
+
+#[test]
+fn test_mission_session() {
+    let af307 = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    // Split the pass into two consecutive bulletins, added out of order
+    let bulletin = |obs_number, obs: &[HDHALog]| {
+        HDOBMessage::from_obs(
+            af307.header.clone(),
+            af307.mission_id.clone(),
+            obs_number,
+            af307.date,
+            obs.to_vec(),
+        )
+        .unwrap()
+    };
+    let session = MissionSession::from_messages([
+        bulletin(16, &af307.obs[10..]),
+        bulletin(15, &af307.obs[..10]),
+    ])
+    .unwrap()
+    .unwrap();
+    assert_eq!("AF307 0606A EARL", session.mission_id());
+    assert_eq!(
+        vec![15, 16],
+        session
+            .messages()
+            .iter()
+            .map(|m| m.obs_number)
+            .collect::<Vec<_>>()
+    );
+
+    // 1011.4 mb in the first bulletin, against 1012.0 mb in the second
+    assert_eq!(Some(&af307.obs[0]), session.min_pressure_over_session());
+    assert_eq!(af307.peak_wind_obs(), session.peak_winds_over_session());
+    let track = session.center_track();
+    assert_eq!(2, track.len());
+    assert_eq!(af307.obs[0].location, track[0].location);
+    assert!(track[0].time < track[1].time);
+
+    let af308 = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220905-09-HDOB-EARL-1006A-AF308.txt"
+    ))
+    .unwrap();
+    let mut session = session;
+    assert_eq!(
+        Err(MergeError::MissionMismatch {
+            left: "AF307 0606A EARL".to_string(),
+            right: "AF308 1006A EARL".to_string(),
+        }),
+        session.add(af308)
+    );
+    assert!(MissionSession::from_messages([]).unwrap().is_none());
+}
+
+/// Sorts bulletins into their missions, keyed by mission identifier, each mission's bulletins
+/// in order of their first observation. Each group can be made into a [`MissionSession`].
+pub fn group_by_mission(
+    messages: impl IntoIterator<Item = HDOBMessage>,
+) -> HashMap<String, Vec<HDOBMessage>> {
+    let mut missions: HashMap<String, Vec<HDOBMessage>> = HashMap::new();
+    for message in messages {
+        missions
+            .entry(message.mission_id.clone())
+            .or_default()
+            .push(message);
+    }
+    for messages in missions.values_mut() {
+        messages.sort_by_key(|m| m.first_obs().map(|ob| ob.time));
+    }
+    missions
+}
+
+#[test]
+fn test_group_by_mission() {
+    let af307 = include_str!("../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt");
+    let af308 = include_str!("../testdata/hdob/20220905-09-HDOB-EARL-1006A-AF308.txt");
+    let noaa2 = include_str!("../testdata/hdob/20220905-31-HDOB-EARL-0906A-NOAA2.txt");
+    // AF308's next bulletin, two hours later, arriving out of order
+    let af308_next = af308
+        .replace("HDOB 09", "HDOB 10")
+        .replace("\n17", "\n19")
+        .replace("\n16", "\n18");
+    // NOAA2's bulletin has no $$, so it must come last
+    let feed = [af307, &af308_next, af308, noaa2].join("\n");
+
+    let messages = HDOBMessage::parse_many(&feed)
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(4, messages.len());
+    let mut missions = group_by_mission(messages);
+    let mut ids: Vec<&str> = missions.keys().map(String::as_str).collect();
+    ids.sort();
+    assert_eq!(
+        vec!["AF307 0606A EARL", "AF308 1006A EARL", "NOAA2 0906A EARL"],
+        ids
+    );
+    let numbers: Vec<u32> = missions["AF308 1006A EARL"]
+        .iter()
+        .map(|m| m.obs_number)
+        .collect();
+    assert_eq!(vec![9, 10], numbers);
+    assert_eq!(1, missions["NOAA2 0906A EARL"].len());
+
+    let af308_bulletins = missions.remove("AF308 1006A EARL").unwrap();
+    let session = MissionSession::from_messages(af308_bulletins).unwrap();
+    assert_eq!(2, session.unwrap().messages().len());
+}
+
+/// Counts of questionable and missing data over a set of observations, as tallied by
+/// [`QualityStats`].
+///
+/// The questionable counts follow the HDOB quality flags; the missing counts cover the same
+/// seven fields as [`HDOBMessage::completeness`].
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct QualityCounts {
+    pub obs: usize,
+    /// Observations with any quality flag set.
+    pub flagged: usize,
+    pub latlon_questionable: usize,
+    pub altitude_or_pressure_questionable: usize,
+    pub temp_or_dewpoint_questionable: usize,
+    pub winds_questionable: usize,
+    pub sfmr_questionable: usize,
+    pub missing_surface_pressure: usize,
+    pub missing_temp: usize,
+    pub missing_dewpoint: usize,
+    pub missing_wind: usize,
+    pub missing_peak_wind: usize,
+    pub missing_sfmr: usize,
+    pub missing_rain_rate: usize,
+}
+
+impl QualityCounts {
+    const FIELDS_PER_OB: usize = 7;
+
+    fn add(&mut self, ob: &HDHALog) {
+        let flags = ob.quality_flags();
+        self.obs += 1;
+        self.flagged += flags.any() as usize;
+        self.latlon_questionable += flags.latlon_questionable as usize;
+        self.altitude_or_pressure_questionable += flags.altitude_or_pressure_questionable as usize;
+        self.temp_or_dewpoint_questionable += flags.temp_or_dewpoint_questionable as usize;
+        self.winds_questionable += flags.winds_questionable as usize;
+        self.sfmr_questionable += flags.sfmr_questionable as usize;
+        self.missing_surface_pressure += ob.surface_pressure.is_none() as usize;
+        self.missing_temp += ob.temp.is_none() as usize;
+        self.missing_dewpoint += ob.dewpoint.is_none() as usize;
+        self.missing_wind += ob.wind.is_none() as usize;
+        self.missing_peak_wind += ob.peak_wind_speed.is_none() as usize;
+        self.missing_sfmr += ob.peak_sfmr_speed.is_none() as usize;
+        self.missing_rain_rate += ob.rain_rate.is_none() as usize;
+    }
+
+    /// The missing fields summed over all seven kinds.
+    pub fn missing_fields(&self) -> usize {
+        self.missing_surface_pressure
+            + self.missing_temp
+            + self.missing_dewpoint
+            + self.missing_wind
+            + self.missing_peak_wind
+            + self.missing_sfmr
+            + self.missing_rain_rate
+    }
+
+    /// The fraction of observations with any quality flag set, or 0 with no observations.
+    pub fn flagged_ratio(&self) -> f64 {
+        if self.obs == 0 {
+            return 0.0;
+        }
+        self.flagged as f64 / self.obs as f64
+    }
+
+    /// The fraction of fields missing, or 0 with no observations.
+    pub fn missing_ratio(&self) -> f64 {
+        if self.obs == 0 {
+            return 0.0;
+        }
+        self.missing_fields() as f64 / (self.obs * Self::FIELDS_PER_OB) as f64
+    }
+}
+
+/// Data quality tallied across any number of bulletins, such as a whole season's feed, in
+/// total and per mission. Fold bulletins in with [`QualityStats::update`]; each is counted as
+/// given, so a bulletin folded in twice is counted twice.
+#[derive(Debug, Clone, Default)]
+pub struct QualityStats {
+    bulletins: usize,
+    total: QualityCounts,
+    missions: HashMap<String, QualityCounts>,
+}
+
+impl QualityStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, msg: &HDOBMessage) {
+        self.bulletins += 1;
+        let mission = self.missions.entry(msg.mission_id.clone()).or_default();
+        for ob in &msg.obs {
+            self.total.add(ob);
+            mission.add(ob);
+        }
+    }
+
+    /// The number of bulletins folded in.
+    pub fn bulletins(&self) -> usize {
+        self.bulletins
+    }
+
+    pub fn total(&self) -> &QualityCounts {
+        &self.total
+    }
+
+    /// The counts for one mission, or `None` if none of its bulletins have been seen.
+    pub fn mission(&self, mission_id: &str) -> Option<&QualityCounts> {
+        self.missions.get(mission_id)
+    }
+
+    /// The counts for every mission seen, in no particular order.
+    pub fn missions(&self) -> impl Iterator<Item = (&str, &QualityCounts)> {
+        self.missions
+            .iter()
+            .map(|(id, counts)| (id.as_str(), counts))
+    }
+}
+
+#[test]
+fn test_quality_stats() {
+    let af307 = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt"
+    ))
+    .unwrap();
+    let kay = HDOBMessage::parse(include_str!(
+        "../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt"
+    ))
+    .unwrap();
+    let counts = |msg: &HDOBMessage| {
+        let mut stats = QualityStats::new();
+        stats.update(msg);
+        *stats.total()
+    };
+
+    // SFMR and rain rate questionable on three of twenty observations, and missing on two
+    let af307_counts = counts(&af307);
+    assert_eq!(20, af307_counts.obs);
+    assert_eq!(3, af307_counts.flagged);
+    assert_eq!(3, af307_counts.sfmr_questionable);
+    assert_eq!(2, af307_counts.missing_sfmr);
+    assert_eq!(4, af307_counts.missing_fields());
+    assert_eq!(4.0 / 140.0, af307_counts.missing_ratio());
+    assert_eq!(0.15, af307_counts.flagged_ratio());
+    let kay_counts = counts(&kay);
+
+    let mut stats = QualityStats::new();
+    stats.update(&af307);
+    stats.update(&kay);
+    assert_eq!(2, stats.bulletins());
+    let total = stats.total();
+    assert_eq!(af307_counts.obs + kay_counts.obs, total.obs);
+    assert_eq!(af307_counts.flagged + kay_counts.flagged, total.flagged);
+    assert_eq!(
+        af307_counts.sfmr_questionable + kay_counts.sfmr_questionable,
+        total.sfmr_questionable
+    );
+    assert_eq!(
+        af307_counts.temp_or_dewpoint_questionable + kay_counts.temp_or_dewpoint_questionable,
+        total.temp_or_dewpoint_questionable
+    );
+    assert_eq!(
+        af307_counts.missing_fields() + kay_counts.missing_fields(),
+        total.missing_fields()
+    );
+    assert_eq!(Some(&af307_counts), stats.mission("AF307 0606A EARL"));
+    assert_eq!(Some(&kay_counts), stats.mission("AF309 0112E KAY"));
+    assert_eq!(None, stats.mission("NOAA2 0906A EARL"));
+    assert_eq!(2, stats.missions().count());
+
+    // A second bulletin from the same mission adds to its breakdown
+    stats.update(&af307);
+    assert_eq!(40, stats.mission("AF307 0606A EARL").unwrap().obs);
+    assert_eq!(af307_counts.obs * 2 + kay_counts.obs, stats.total().obs);
+    assert_eq!(0.0, QualityCounts::default().missing_ratio());
+}
+
+/// Conditions estimated between observations by [`HDOBMessage::interpolate_at`].
+///
+/// Each quantity is interpolated linearly; one missing at either neighbouring observation is
+/// missing here too. Winds turn the short way around the compass.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterpolatedObs {
+    pub time: DateTime<Utc>,
+    pub location: Coordinate,
+    pub aircraft_pressure: Pressure,
+    pub height: Altitude,
+    pub surface_pressure: Option<ExtrapolatedSurfacePressure>,
+    pub temp: Option<Temperature>,
+    pub dewpoint: Option<Temperature>,
+    pub wind: Option<Wind>,
+    pub peak_wind_speed: Option<Speed>,
+    pub peak_sfmr_speed: Option<Speed>,
+    pub rain_rate: Option<RainRate>,
+}
+
+impl InterpolatedObs {
+    fn between(a: &HDHALog, b: &HDHALog, fraction: f64) -> Self {
+        let lerp = |a: f64, b: f64| a + (b - a) * fraction;
+        let lerp_i32 = |a: i32, b: i32| lerp(a as f64, b as f64).round() as i32;
+        let lerp_u32 = |a: u32, b: u32| lerp(a as f64, b as f64).round() as u32;
+
+        let surface_pressure = match (a.surface_pressure, b.surface_pressure) {
+            (
+                Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(pa)),
+                Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(pb)),
+            ) => Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(
+                Pressure::with_microbars(lerp_i32(pa.microbars(), pb.microbars())),
+            )),
+            (
+                Some(ExtrapolatedSurfacePressure::DValue(da)),
+                Some(ExtrapolatedSurfacePressure::DValue(db)),
+            ) => Some(ExtrapolatedSurfacePressure::DValue(DValue::with_meters(
+                lerp_i32(da.meters(), db.meters()),
+            ))),
+            _ => None,
+        };
+        let temperature = |ta: Option<Temperature>, tb: Option<Temperature>| {
+            ta.zip(tb).map(|(ta, tb)| {
+                Temperature::with_millikelvin(lerp_u32(ta.millikelvin(), tb.millikelvin()))
+            })
+        };
+        let lerp_speed =
+            |sa: Speed, sb: Speed| Speed::with_knots_f64(lerp(sa.knots_f64(), sb.knots_f64()));
+        let speed =
+            |sa: Option<Speed>, sb: Option<Speed>| sa.zip(sb).map(|(sa, sb)| lerp_speed(sa, sb));
+        let wind = a.wind.zip(b.wind).map(|(wa, wb)| {
+            let da = wa.direction.angle().decimal_degrees();
+            let db = wb.direction.angle().decimal_degrees();
+            let turn = (db - da + 540.0).rem_euclid(360.0) - 180.0;
+            Wind::with_direction_and_speed(
+                Direction::from_degrees(da + turn * fraction),
+                lerp_speed(wa.speed, wb.speed),
+            )
+        });
+
+        Self {
+            time: a.time
+                + Duration::milliseconds(
+                    lerp(0.0, (b.time - a.time).num_milliseconds() as f64).round() as i64,
+                ),
+            location: a.location.interpolate(&b.location, fraction),
+            aircraft_pressure: Pressure::with_microbars(lerp_i32(
+                a.aircraft_pressure.microbars(),
+                b.aircraft_pressure.microbars(),
+            )),
+            height: Altitude::with_meters(lerp_u32(a.height.meters(), b.height.meters())),
+            surface_pressure,
+            temp: temperature(a.temp, b.temp),
+            dewpoint: temperature(a.dewpoint, b.dewpoint),
+            wind,
+            peak_wind_speed: speed(a.peak_wind_speed, b.peak_wind_speed),
+            peak_sfmr_speed: speed(a.peak_sfmr_speed, b.peak_sfmr_speed),
+            rain_rate: a
+                .rain_rate
+                .zip(b.rain_rate)
+                .map(|(ra, rb)| RainRate::with_mm_per_hr(lerp_u32(ra.mm_per_hr(), rb.mm_per_hr()))),
+        }
+    }
+}
+
+/// The quality control flags of an [`HDHALog`], decoded from its final two-digit group.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct QualityFlags {
+    pub latlon_questionable: bool,
+    pub altitude_or_pressure_questionable: bool,
+    pub temp_or_dewpoint_questionable: bool,
+    pub winds_questionable: bool,
+    pub sfmr_questionable: bool,
+}
+
+impl QualityFlags {
+    /// Whether any flag is set.
+    pub fn any(&self) -> bool {
+        self.latlon_questionable
+            || self.altitude_or_pressure_questionable
+            || self.temp_or_dewpoint_questionable
+            || self.winds_questionable
+            || self.sfmr_questionable
+    }
+}
+
+/// One 30-second observation from an HDOB data line.
+///
+/// Prefer the accessor methods to the fields, which will become private in a future release
+/// so that invariants can be enforced. The time, location and sea-level pressure are read
+/// through [`MetObservation`]; [`HDHALog::extrapolated_surface_pressure`] gives the pressure
+/// group as reported.
+///
+/// Observations compare equal when their values do, whichever line of whichever bulletin they
+/// were parsed from and whether or not [`HDOBMessage::fill_missing`] supplied any of them.
+#[derive(Debug, Clone)]
+pub struct HDHALog {
+    pub time: DateTime<Utc>,
+    pub location: Coordinate,
+    pub aircraft_pressure: Pressure,
+    pub height: Altitude,
+    pub surface_pressure: Option<ExtrapolatedSurfacePressure>,
+    pub temp: Option<Temperature>,
+    pub dewpoint: Option<Temperature>,
+    pub wind: Option<Wind>,
+    pub peak_wind_speed: Option<Speed>,
+    pub peak_sfmr_speed: Option<Speed>,
+    pub rain_rate: Option<RainRate>,
+    pub latlon_questionable: bool,
+    pub altitude_or_pressure_questionable: bool,
+    pub temp_or_dewpoint_questionable: bool,
+    pub winds_questionable: bool,
+    pub sfmr_questionable: bool,
+    /// The significant wave height, from the provisional sea-state groups of [`HDOB_COLUMNS`].
+    pub wave_height: Option<Altitude>,
+    /// The sea surface temperature, from the provisional sea-state groups of [`HDOB_COLUMNS`].
+    pub sst: Option<Temperature>,
+    /// The 1-based number of the line this observation was parsed from, within its bulletin.
+    /// A line parsed on its own is line 1.
+    source_line: usize,
+    /// Whether any value was interpolated by [`HDOBMessage::fill_missing`] rather than
+    /// observed.
+    filled: bool,
+}
+
+impl PartialEq for HDHALog {
+    fn eq(&self, other: &Self) -> bool {
+        // Destructured so that a new field can't be left out of the comparison unnoticed
+        let HDHALog {
+            time,
+            location,
+            aircraft_pressure,
+            height,
+            surface_pressure,
+            temp,
+            dewpoint,
+            wind,
+            peak_wind_speed,
+            peak_sfmr_speed,
+            rain_rate,
+            latlon_questionable,
+            altitude_or_pressure_questionable,
+            temp_or_dewpoint_questionable,
+            winds_questionable,
+            sfmr_questionable,
+            wave_height,
+            sst,
+            source_line: _,
+            filled: _,
+        } = self;
+        *time == other.time
+            && *location == other.location
+            && *aircraft_pressure == other.aircraft_pressure
+            && *height == other.height
+            && *surface_pressure == other.surface_pressure
+            && *temp == other.temp
+            && *dewpoint == other.dewpoint
+            && *wind == other.wind
+            && *peak_wind_speed == other.peak_wind_speed
+            && *peak_sfmr_speed == other.peak_sfmr_speed
+            && *rain_rate == other.rain_rate
+            && *latlon_questionable == other.latlon_questionable
+            && *altitude_or_pressure_questionable == other.altitude_or_pressure_questionable
+            && *temp_or_dewpoint_questionable == other.temp_or_dewpoint_questionable
+            && *winds_questionable == other.winds_questionable
+            && *sfmr_questionable == other.sfmr_questionable
+            && *wave_height == other.wave_height
+            && *sst == other.sst
+    }
+}
+
+impl HDHALog {
+    /// The static air pressure at the aircraft.
+    pub fn aircraft_pressure(&self) -> Pressure {
+        self.aircraft_pressure
+    }
+
+    /// The geopotential height of the aircraft.
+    pub fn height(&self) -> Altitude {
+        self.height
+    }
+
+    /// The extrapolated surface pressure, or the D-value at high altitude.
+    pub fn extrapolated_surface_pressure(&self) -> Option<ExtrapolatedSurfacePressure> {
+        self.surface_pressure
+    }
+
+    /// The flight-level air temperature.
+    pub fn temp(&self) -> Option<Temperature> {
+        self.temp
+    }
+
+    /// The flight-level dewpoint.
+    pub fn dewpoint(&self) -> Option<Temperature> {
+        self.dewpoint
+    }
+
+    /// The 30-second average flight-level wind, typed so it can't be mistaken for a surface
+    /// wind. [`MetObservation::wind`] gives the same wind untyped.
+    pub fn flight_level_wind(&self) -> Option<FlightLevelWind> {
+        self.wind.map(FlightLevelWind)
+    }
+
+    /// The peak 10-second average flight-level wind speed.
+    pub fn peak_wind_speed(&self) -> Option<Speed> {
+        self.peak_wind_speed
+    }
+
+    /// The peak 10-second average SFMR surface wind speed.
+    pub fn peak_sfmr_speed(&self) -> Option<Speed> {
+        self.peak_sfmr_speed
+    }
+
+    /// The peak SFMR surface wind speed, typed so it can't be mistaken for a flight-level wind.
+    pub fn sfmr_wind(&self) -> Option<SurfaceWind> {
+        self.peak_sfmr_speed.map(SurfaceWind)
+    }
+
+    /// The SFMR-derived rain rate.
+    pub fn rain_rate(&self) -> Option<RainRate> {
+        self.rain_rate
+    }
+
+    pub fn latlon_questionable(&self) -> bool {
+        self.latlon_questionable
+    }
+
+    pub fn altitude_or_pressure_questionable(&self) -> bool {
+        self.altitude_or_pressure_questionable
+    }
+
+    pub fn temp_or_dewpoint_questionable(&self) -> bool {
+        self.temp_or_dewpoint_questionable
+    }
+
+    pub fn winds_questionable(&self) -> bool {
+        self.winds_questionable
+    }
+
+    pub fn sfmr_questionable(&self) -> bool {
+        self.sfmr_questionable
+    }
+
+    /// The significant wave height, if the line carried one.
+    pub fn wave_height(&self) -> Option<Altitude> {
+        self.wave_height
+    }
+
+    /// The sea surface temperature, if the line carried one.
+    pub fn sst(&self) -> Option<Temperature> {
+        self.sst
+    }
+
+    /// The line of the bulletin this observation was parsed from, counting from 1.
+    pub fn source_line(&self) -> usize {
+        self.source_line
+    }
+
+    /// Whether any value was interpolated by [`HDOBMessage::fill_missing`].
+    pub fn filled(&self) -> bool {
+        self.filled
+    }
+
+    /// All of the quality flags together.
+    pub fn quality_flags(&self) -> QualityFlags {
+        QualityFlags {
+            latlon_questionable: self.latlon_questionable,
+            altitude_or_pressure_questionable: self.altitude_or_pressure_questionable,
+            temp_or_dewpoint_questionable: self.temp_or_dewpoint_questionable,
+            winds_questionable: self.winds_questionable,
+            sfmr_questionable: self.sfmr_questionable,
+        }
+    }
+
+    pub fn parse(date: &NaiveDate, line: &str) -> Result<Self, ParseError> {
+        Self::parse_with(date, line, &ParseOptions::default())
+    }
+
+    pub fn parse_with(
+        date: &NaiveDate,
+        line: &str,
+        options: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        // Tokens arrive in HDOB_COLUMNS order
+        let mut cols = split_columns(line)?.into_iter();
+        let mut next = || cols.next().unwrap();
+
+        let time = parse_hhmmss(date, next())?;
+        let location = parse_latlon(next(), next())?;
+        let aircraft_pressure = parse_aircraft_pressure(next())?;
+        let height = next();
+        if is_missing(height) {
+            return Err(ParseError::Missing("height"));
+        }
+        let height = Altitude::with_meters(
+            height
+                .parse()
+                .map_err(|_| ParseError::invalid("height", height))?,
+        );
+        let surface_pressure = parse_extrapolated_sfc_pressure(aircraft_pressure, next())?;
+        let temp = parse_temperature("temp", next(), options.temperature_scale)?;
+        let dewpoint = parse_temperature("dewpoint", next(), options.temperature_scale)?;
+        let wind = parse_wind(next());
+        let peak_wind_speed = parse_speed(next());
+        let peak_sfmr_speed = parse_speed(next());
+        let rain_rate = parse_rain_rate(next())?;
+
+        let quality_text = next();
+        let quality = quality_text
+            .parse::<u8>()
+            .map_err(|_| ParseError::invalid("quality", quality_text))?;
+        let unknown_code = || match options.quality_mode {
+            QualityMode::Strict => Err(ParseError::invalid("quality", quality_text)),
+            QualityMode::Lenient => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(%time, quality = quality_text, "unknown quality code");
+                Ok(())
+            }
+        };
+
+        let (latlon_questionable, altitude_or_pressure_questionable) = match quality / 10 {
+            0 => (false, false),
+            1 => (true, false),
+            2 => (false, true),
+            3 => (true, true),
+            _ => {
+                unknown_code()?;
+                (true, true)
+            }
+        };
+
+        let (temp_or_dewpoint_questionable, winds_questionable, sfmr_questionable) =
+            match quality % 10 {
+                0 => (false, false, false),
+                1 => (true, false, false),
+                2 => (false, true, false),
+                3 => (false, false, true),
+                4 => (true, true, false),
+                5 => (true, false, true),
+                6 => (false, true, true),
+                9 => (true, true, true),
+                _ => {
+                    unknown_code()?;
+                    (true, true, true)
+                }
+            };
+
+        // The sea-state layout is provisional, so trailing groups that don't fit it are ignored
+        // rather than failing a line that is otherwise good.
+        let wave_height = cols.next().map(parse_wave_height);
+        let sst = cols
+            .next()
+            .map(|sttt| parse_temperature("sst", sttt, options.temperature_scale));
+        let (wave_height, sst) = match (wave_height, sst) {
+            (Some(Err(_)), _) | (_, Some(Err(_))) => (None, None),
+            (wave_height, sst) => (
+                wave_height.and_then(Result::ok).flatten(),
+                sst.and_then(Result::ok).flatten(),
+            ),
+        };
+
+        let ob = HDHALog {
+            time,
+            location,
+            aircraft_pressure,
+            height,
+            surface_pressure,
+            temp,
+            dewpoint,
+            wind,
+            peak_wind_speed,
+            peak_sfmr_speed,
+            rain_rate,
+            latlon_questionable,
+            altitude_or_pressure_questionable,
+            temp_or_dewpoint_questionable,
+            winds_questionable,
+            sfmr_questionable,
+            wave_height,
+            sst,
+            source_line: 1,
+            filled: false,
+        };
+        #[cfg(feature = "tracing")]
+        ob.trace_parse(quality_text);
+        Ok(ob)
+    }
+
+    /// Reports questionable and missing fields to `tracing`, for monitoring data quality.
+    #[cfg(feature = "tracing")]
+    fn trace_parse(&self, quality: &str) {
+        if quality != "00" {
+            tracing::warn!(time = %self.time, quality, "observation flagged questionable");
+        }
+        let missing: Vec<&str> = [
+            ("esp", self.surface_pressure.is_none()),
+            ("temp", self.temp.is_none()),
+            ("dewpoint", self.dewpoint.is_none()),
+            ("wind", self.wind.is_none()),
+            ("peak_wind", self.peak_wind_speed.is_none()),
+            ("sfmr", self.peak_sfmr_speed.is_none()),
+            ("rain_rate", self.rain_rate.is_none()),
+        ]
+        .into_iter()
+        .filter_map(|(name, missing)| missing.then_some(name))
+        .collect();
+        if !missing.is_empty() {
+            tracing::debug!(time = %self.time, ?missing, "observation has missing fields");
+        }
+    }
+
+    /// Parses a data line as [`HDHALog::parse`] does, also returning the raw token behind each
+    /// field for diagnosing misparses.
+    pub fn parse_debug(date: &NaiveDate, line: &str) -> Result<(Self, RawFields), ParseError> {
+        let raw = RawFields::from_line(line)?;
+        Ok((Self::parse(date, line)?, raw))
+    }
+
+    /// Renders this observation back into a single HDOB data line.
+    pub fn to_hdob(&self, options: &EncodeOptions) -> String {
+        let line = format!(
+            "{} {} {} {} {:05} {} {} {} {} {} {} {} {}",
+            encode_hhmmss(&self.time),
+            self.location.latitude.to_hdob(),
+            self.location.longitude.to_hdob(),
+            encode_aircraft_pressure(self.aircraft_pressure, options.rounding),
+            self.height.meters(),
+            encode_extrapolated_sfc_pressure(self.surface_pressure, options.rounding),
+            encode_temperature(self.temp, options.rounding),
+            encode_temperature(self.dewpoint, options.rounding),
+            encode_wind(self.wind),
+            encode_speed(self.peak_wind_speed),
+            encode_speed(self.peak_sfmr_speed),
+            encode_rain_rate(self.rain_rate),
+            self.encode_quality(),
+        );
+        if self.wave_height.is_none() && self.sst.is_none() {
+            return line;
+        }
+        let wave_height = self
+            .wave_height
+            .map_or("//".to_string(), |h| format!("{:02}", h.meters()));
+        format!(
+            "{} {} {}",
+            line,
+            wave_height,
+            encode_temperature(self.sst, options.rounding)
+        )
+    }
+
+    /// The D-value implied by this observation's geopotential height and aircraft pressure,
+    /// i.e. how far the aircraft is above the same pressure surface in the standard atmosphere.
+    pub fn dvalue_consistency(&self) -> Option<DValue> {
+        let standard = self.aircraft_pressure.standard_height()?;
+        Some(DValue::with_meters(
+            (self.height.meters() as f64 - standard).round() as i32,
+        ))
+    }
+
+    /// The flight-level virtual temperature, the temperature dry air would need to have the
+    /// same density as this moist air, or `None` without a temperature and dewpoint.
+    ///
+    /// `Tᵥ = T (1 + 0.61 w)`, with mixing ratio `w = 0.622 e / (p − e)` at the aircraft pressure
+    /// `p` and vapor pressure `e = 6.112 exp(17.67 Td / (Td + 243.5))` mb (Bolton, 1980), `Td`
+    /// being the dewpoint in °C.
+    pub fn virtual_temperature(&self) -> Option<Temperature> {
+        let p = self.aircraft_pressure.microbars() as f64 / 1000.0;
+        let tv = virtual_temperature(p, self.temp?, self.dewpoint?);
+        Temperature::try_with_millikelvin((tv * 1000.0).round() as i64)
+    }
+
+    /// Splits the flight-level wind, in knots, into a headwind component along `heading` (the
+    /// direction the aircraft is moving, e.g. from [`Coordinate::bearing_to`] between
+    /// consecutive positions) and a crosswind component across it. A negative headwind is a
+    /// tailwind; a positive crosswind blows from the right, a negative one from the left.
+    /// Returns `None` without a wind.
+    pub fn wind_along_cross(&self, heading: Direction) -> Option<(f64, f64)> {
+        let wind = self.wind?;
+        let relative = (wind.direction.angle().decimal_degrees()
+            - heading.angle().decimal_degrees())
+        .to_radians();
+        let speed = wind.speed.knots_f64();
+        Some((speed * relative.cos(), speed * relative.sin()))
+    }
+
+    /// The density of the air at flight level, in kg/m³, from the ideal gas law
+    /// `ρ = p / (R_d T)`. The virtual temperature is used for `T` when there is a dewpoint,
+    /// otherwise the air is taken as dry. Returns `None` without a temperature.
+    pub fn air_density(&self) -> Option<f64> {
+        const R_D: f64 = 287.05;
+
+        let t = match self.virtual_temperature() {
+            Some(tv) => tv,
+            None => self.temp?,
+        };
+        let pa = self.aircraft_pressure.microbars() as f64 / 10.0;
+        Some(pa / (R_D * t.millikelvin() as f64 / 1000.0))
+    }
+
+    /// The height, in meters, at which the U.S. Standard Atmosphere has the same density as the
+    /// air at flight level (see [`HDHALog::air_density`]). It exceeds the actual height in air
+    /// warmer than standard.
+    ///
+    /// `h = (T₀ / L) (1 − (ρ / ρ₀)^(1 / (g / (R_d L) − 1)))`, with `T₀` = 288.15 K,
+    /// `L` = 6.5 K/km, and `ρ₀` = 1.225 kg/m³. Only valid in the troposphere.
+    pub fn density_altitude(&self) -> Option<f64> {
+        const T0: f64 = 288.15;
+        const LAPSE_RATE: f64 = 0.0065;
+        const RHO_0: f64 = 1.225;
+        const EXPONENT: f64 = 1.0 / 4.255_88;
+
+        let rho = self.air_density()?;
+        Some(T0 / LAPSE_RATE * (1.0 - (rho / RHO_0).powf(EXPONENT)))
+    }
+
+    /// Reduces the aircraft pressure to mean sea level with the hypsometric equation,
+    ///
+    /// `P₀ = P exp(g z / (R_d T̄ᵥ))`,
+    ///
+    /// where `z` is the geopotential height and `T̄ᵥ` the mean virtual temperature of the layer
+    /// below the aircraft. `T̄ᵥ` is the observed flight-level virtual temperature (from the
+    /// temperature and dewpoint) warmed by the standard 6.5 K/km lapse rate over half the layer.
+    ///
+    /// Without a temperature and dewpoint, the U.S. Standard Atmosphere is assumed instead. Above
+    /// [`DVALUE_PRESSURE_THRESHOLD`] the layer is too deep to reduce through, and `None` is
+    /// returned, as the transmitted extrapolated surface pressure would be. `None` is also
+    /// returned if the result is implausible, which means the inputs were.
+    pub fn reduce_to_msl(&self) -> Option<SurfacePressure> {
+        const G: f64 = 9.806_65;
+        const R_D: f64 = 287.05;
+        const LAPSE_RATE: f64 = 0.0065;
+
+        if self.aircraft_pressure < DVALUE_PRESSURE_THRESHOLD {
+            return None;
+        }
+        let p = self.aircraft_pressure.microbars() as f64 / 1000.0;
+        let z = self.height.meters() as f64;
+        let msl = match (self.temp, self.dewpoint) {
+            (Some(temp), Some(dewpoint)) => {
+                let mean_tv = virtual_temperature(p, temp, dewpoint) + LAPSE_RATE * z / 2.0;
+                p * (G * z / (R_D * mean_tv)).exp()
+            }
+            _ => p * (1.0 - LAPSE_RATE * z / 288.15).powf(-5.255_88),
+        };
+        SurfacePressure::new(Pressure::with_millibars_f64(msl))
+    }
+
+    /// The surface pressure for this observation: the transmitted extrapolated surface pressure
+    /// or, if it is missing, [`HDHALog::reduce_to_msl`]. Returns `None` if neither yields a
+    /// plausible pressure, including for high-altitude observations reporting D-values.
+    pub fn resolved_surface_pressure(&self) -> Option<SurfacePressure> {
+        match self.surface_pressure {
+            Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(p)) => SurfacePressure::new(p),
+            Some(ExtrapolatedSurfacePressure::DValue(_)) => None,
+            None => self.reduce_to_msl(),
+        }
+    }
+
+    /// Checks this observation for internally inconsistent or implausible values, with the
+    /// default [`SanityLimits`].
+    pub fn sanity_check(&self) -> Vec<SanityViolation> {
+        self.sanity_check_with(&SanityLimits::default())
+    }
+
+    pub fn sanity_check_with(&self, limits: &SanityLimits) -> Vec<SanityViolation> {
+        let mut violations = vec![];
+
+        if let (Some(ExtrapolatedSurfacePressure::DValue(reported)), Some(implied)) =
+            (self.surface_pressure, self.dvalue_consistency())
+        {
+            if (reported.meters() - implied.meters()).abs() > DVALUE_TOLERANCE {
+                violations.push(SanityViolation::DValueMismatch { reported, implied });
+            }
+        }
+        if let Some(rain_rate) = self.rain_rate {
+            if rain_rate > limits.max_rain_rate {
+                violations.push(SanityViolation::ImplausibleRainRate(rain_rate));
+            }
+        }
+
+        violations
+    }
+
+    /// Whether `other` is the same observation as this one up to the rounding differences of
+    /// another relay: the times must match exactly, as must the rain rate and quality flags,
+    /// and every other field within the tolerances of `opts`. A field missing from one
+    /// observation must be missing from both.
+    pub fn approx_eq(&self, other: &HDHALog, opts: ApproxOpts) -> bool {
+        fn both<T>(a: Option<T>, b: Option<T>, eq: impl Fn(&T, &T) -> bool) -> bool {
+            match (a, b) {
+                (Some(a), Some(b)) => eq(&a, &b),
+                (None, None) => true,
+                _ => false,
+            }
+        }
+        let degrees = opts.position.decimal_degrees();
+        let temperature = |a: &Temperature, b: &Temperature| a.approx_eq(b, opts.temperature);
+        let speed = |a: &Speed, b: &Speed| a.approx_eq(b, opts.speed);
+        let surface_pressure = |a: &ExtrapolatedSurfacePressure,
+                                b: &ExtrapolatedSurfacePressure| {
+            use ExtrapolatedSurfacePressure::{DValue as D, ExtrapolatedPressure as Esp};
+            match (a, b) {
+                (Esp(a), Esp(b)) => a.approx_eq(b, opts.pressure),
+                (D(a), D(b)) => a.approx_eq(b, DValue::with_meters(opts.height.meters() as i32)),
+                _ => false,
+            }
+        };
+        let wind = |a: &Wind, b: &Wind| {
+            let turn = (a.direction.angle().decimal_degrees()
+                - b.direction.angle().decimal_degrees())
+            .rem_euclid(360.0);
+            turn.min(360.0 - turn) <= opts.direction.decimal_degrees()
+                && a.speed.approx_eq(&b.speed, opts.speed)
+        };
+
+        self.time == other.time
+            && self
+                .location
+                .latitude
+                .approx_eq(&other.location.latitude, degrees)
+            && self
+                .location
+                .longitude
+                .approx_eq(&other.location.longitude, degrees)
+            && self
+                .aircraft_pressure
+                .approx_eq(&other.aircraft_pressure, opts.pressure)
+            && self.height.approx_eq(&other.height, opts.height)
+            && both(
+                self.surface_pressure,
+                other.surface_pressure,
+                surface_pressure,
+            )
+            && both(self.temp, other.temp, temperature)
+            && both(self.dewpoint, other.dewpoint, temperature)
+            && both(self.wind, other.wind, wind)
+            && both(self.peak_wind_speed, other.peak_wind_speed, speed)
+            && both(self.peak_sfmr_speed, other.peak_sfmr_speed, speed)
+            && self.rain_rate == other.rain_rate
+            && self.quality_flags() == other.quality_flags()
+            && both(self.wave_height, other.wave_height, |a, b| {
+                a.approx_eq(b, opts.height)
+            })
+            && both(self.sst, other.sst, temperature)
+    }
+
+    fn encode_quality(&self) -> String {
+        let pos = match (
+            self.latlon_questionable,
+            self.altitude_or_pressure_questionable,
+        ) {
+            (false, false) => 0,
+            (true, false) => 1,
+            (false, true) => 2,
+            (true, true) => 3,
+        };
+        let met = match (
+            self.temp_or_dewpoint_questionable,
+            self.winds_questionable,
+            self.sfmr_questionable,
+        ) {
+            (false, false, false) => 0,
+            (true, false, false) => 1,
+            (false, true, false) => 2,
+            (false, false, true) => 3,
+            (true, true, false) => 4,
+            (true, false, true) => 5,
+            (false, true, true) => 6,
+            (true, true, true) => 9,
+        };
+        format!("{}{}", pos, met)
+    }
+}
+
+/// The virtual temperature, in K, of air at pressure `p` (mb) with the given temperature and
+/// dewpoint, using Bolton's (1980) vapor pressure formula.
+fn virtual_temperature(p: f64, temp: Temperature, dewpoint: Temperature) -> f64 {
+    let t = temp.millikelvin() as f64 / 1000.0;
+    let td = dewpoint.millikelvin() as f64 / 1000.0 - 273.15;
+    let e = 6.112 * (17.67 * td / (td + 243.5)).exp();
+    let mixing_ratio = 0.622 * e / (p - e);
+    t * (1.0 + 0.61 * mixing_ratio)
+}
+
+#[test]
+fn test_virtual_temperature() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+    let ob = HDHALog::parse(
+        &date,
+        "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00",
+    )
+    .unwrap();
+    // e = 6.112 × exp(17.67 × 17.3 / 260.8) = 19.73 mb, w = 0.622 × 19.73 / 903.87 = 0.01358,
+    // Tv = 293.25 × (1 + 0.61 × 0.01358) = 295.68 K
+    assert!(ob.virtual_temperature().unwrap().approx_eq(
+        &Temperature::with_millikelvin(295_680),
+        Temperature::with_millikelvin(100)
+    ));
+
+    let dry = HDHALog {
+        dewpoint: None,
+        ..ob
+    };
+    assert_eq!(None, dry.virtual_temperature());
+}
+
+#[test]
+fn test_wind_along_cross() {
+    use crate::measure::{EAST, NORTH, SOUTH, WEST};
+
+    let date = NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+    // Wind from 090° at 40 kt
+    let ob = HDHALog::parse(
+        &date,
+        "181830 2006N 06141W 9236 00794 0115 +201 +173 090040 041 021 002 00",
+    )
+    .unwrap();
+    let close = |(along, cross): (f64, f64), expected: (f64, f64)| {
+        (along - expected.0).abs() < 1e-9 && (cross - expected.1).abs() < 1e-9
+    };
+    assert!(close(ob.wind_along_cross(EAST).unwrap(), (40.0, 0.0)));
+    assert!(close(ob.wind_along_cross(WEST).unwrap(), (-40.0, 0.0)));
+    // Heading south, an east wind comes from the left
+    assert!(close(ob.wind_along_cross(SOUTH).unwrap(), (0.0, -40.0)));
+    assert!(close(ob.wind_along_cross(NORTH).unwrap(), (0.0, 40.0)));
+
+    let calm = HDHALog { wind: None, ..ob };
+    assert_eq!(None, calm.wind_along_cross(NORTH));
+}
+
+#[test]
+fn test_air_density() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+    // 1013.3 mb and 15.0°C, dry: the standard atmosphere at sea level
+    let standard = HDHALog::parse(
+        &date,
+        "181830 2006N 06141W 0133 00000 0133 +150 //// 123041 041 021 002 00",
+    )
+    .unwrap();
+    assert!((standard.air_density().unwrap() - 1.225).abs() < 0.001);
+    assert!(standard.density_altitude().unwrap().abs() < 10.0);
+
+    // Warm, moist air is less dense, so its density altitude is above its 794 m height
+    let ob = HDHALog::parse(
+        &date,
+        "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00",
+    )
+    .unwrap();
+    // 92360 / (287.05 × 295.68) = 1.0882 kg/m³
+    assert!((ob.air_density().unwrap() - 1.0882).abs() < 0.001);
+    assert!(ob.density_altitude().unwrap() > 794.0);
+
+    let missing = HDHALog { temp: None, ..ob };
+    assert_eq!(None, missing.air_density());
+}
+
+#[test]
+fn test_reduce_to_msl() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+    let ob = HDHALog::parse(
+        &date,
+        "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00",
+    )
+    .unwrap();
+    // e = 19.73 mb, w = 13.6 g/kg, Tv = 295.68 K, mean Tv = 298.26 K,
+    // 923.6 × exp(9.80665 × 794 / (287.05 × 298.26)) = 1011.5 mb: the transmitted value
+    let msl = ob.reduce_to_msl().unwrap().pressure();
+    assert!(msl.approx_eq(
+        &Pressure::with_microbars(1_011_500),
+        Pressure::with_microbars(200)
+    ));
+
+    let dry = HDHALog {
+        dewpoint: None,
+        ..ob
+    };
+    // 923.6 × (1 − 0.0065 × 794 / 288.15)^−5.25588 = 1015.6 mb
+    assert!(dry.reduce_to_msl().unwrap().pressure().approx_eq(
+        &Pressure::with_microbars(1_015_600),
+        Pressure::with_microbars(200)
+    ));
+
+    let high = HDHALog::parse(
+        &date,
+        "155100 2601N 09348W 3926 07749 0432 -173 //// 222015 015 /// /// 05",
+    )
+    .unwrap();
+    assert_eq!(None, high.reduce_to_msl());
+}
+
+#[test]
+fn test_resolved_surface_pressure() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+    let line = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00";
+    let ob = HDHALog::parse(&date, line).unwrap();
+    assert_eq!(
+        Some(Pressure::with_microbars(1_011_500)),
+        ob.resolved_surface_pressure().map(|p| p.pressure())
+    );
+
+    let missing = HDHALog::parse(&date, &line.replace(" 0115 ", " //// ")).unwrap();
+    assert_eq!(missing.reduce_to_msl(), missing.resolved_surface_pressure());
+
+    // A corrupt group resolving to 600 mb
+    let corrupt = HDHALog::parse(&date, &line.replace(" 0115 ", " 6000 ")).unwrap();
+    assert_eq!(
+        Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(
+            Pressure::with_microbars(600_000)
+        )),
+        corrupt.surface_pressure
+    );
+    assert_eq!(None, corrupt.resolved_surface_pressure());
+}
+
+#[test]
+fn test_hdha_accessors() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+    let line = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 01";
+    let ob = HDHALog::parse(&date, line).unwrap();
+    assert_eq!(ob.time, ob.time());
+    assert_eq!(ob.location, ob.location());
+    assert_eq!(
+        Some(Pressure::with_microbars(1_011_500)),
+        ob.surface_pressure()
+    );
+    assert_eq!(Pressure::with_microbars(923_600), ob.aircraft_pressure());
+    assert_eq!(Altitude::with_meters(794), ob.height());
+    assert_eq!(ob.surface_pressure, ob.extrapolated_surface_pressure());
+    assert_eq!(Some(Temperature::with_millicelsius(20_100)), ob.temp());
+    assert_eq!(Some(Temperature::with_millicelsius(17_300)), ob.dewpoint());
+    assert_eq!(Some(Wind::from_degrees_knots(123.0, 41)), ob.wind());
+    assert_eq!(
+        Some(FlightLevelWind(Wind::from_degrees_knots(123.0, 41))),
+        ob.flight_level_wind()
+    );
+    assert_eq!(Some(Speed::with_knots(41)), ob.peak_wind_speed());
+    assert_eq!(Some(Speed::with_knots(21)), ob.peak_sfmr_speed());
+    assert_eq!(Some(SurfaceWind(Speed::with_knots(21))), ob.sfmr_wind());
+    assert_eq!(Some(RainRate::with_mm_per_hr(2)), ob.rain_rate());
+    assert!(!ob.latlon_questionable());
+    assert!(!ob.altitude_or_pressure_questionable());
+    assert!(ob.temp_or_dewpoint_questionable());
+    assert!(!ob.winds_questionable());
+    assert!(!ob.sfmr_questionable());
+}
+
+#[test]
+fn test_sanity_check_dvalue() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 5).unwrap();
+    let consistent = HDHALog::parse(
+        &date,
+        "155100 2601N 09348W 3926 07749 0432 -173 //// 222015 015 /// /// 05",
+    )
+    .unwrap();
+    assert_eq!(
+        Some(DValue::with_meters(432)),
+        consistent.dvalue_consistency()
+    );
+    assert!(consistent.sanity_check().is_empty());
+
+    // Height digits transposed
+    let corrupted = HDHALog::parse(
+        &date,
+        "155100 2601N 09348W 3926 07479 0432 -173 //// 222015 015 /// /// 05",
+    )
+    .unwrap();
+    assert_eq!(
+        vec![SanityViolation::DValueMismatch {
+            reported: DValue::with_meters(432),
+            implied: DValue::with_meters(162),
+        }],
+        corrupted.sanity_check()
+    );
+
+    let kay = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+    let message = HDOBMessage::parse(kay).unwrap();
+    assert!(message.obs.iter().all(|ob| ob.sanity_check().is_empty()));
+}
+
+#[test]
+fn test_sanity_check_rain_rate() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+    let line = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 041 00";
+    let ob = HDHALog::parse(&date, line).unwrap();
+    assert!(ob.rain_rate.unwrap().is_plausible());
+    assert!(ob.sanity_check().is_empty());
+
+    let corrupt = HDHALog::parse(&date, &line.replace(" 041 00", " 999 00")).unwrap();
+    assert!(!corrupt.rain_rate.unwrap().is_plausible());
+    assert_eq!(
+        vec![SanityViolation::ImplausibleRainRate(
+            RainRate::with_mm_per_hr(999)
+        )],
+        corrupt.sanity_check()
+    );
+    let permissive = SanityLimits {
+        max_rain_rate: RainRate::with_mm_per_hr(999),
+    };
+    assert!(corrupt.sanity_check_with(&permissive).is_empty());
+
+    // A misaligned column is malformed rather than a rain rate
+    assert_eq!(
+        Err(ParseError::invalid("rain rate", "0041")),
+        HDHALog::parse(&date, &line.replace(" 041 00", " 0041 00"))
+    );
+}
+
+#[test]
+fn test_approx_eq() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+    let line = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00";
+    let ob = HDHALog::parse(&date, line).unwrap();
+    let relayed = HDHALog::parse(&date, &line.replace("2006N", "2007N")).unwrap();
+    let two_minutes = ApproxOpts {
+        position: Angle::with_degrees_minutes_seconds(0, 2, 0),
+        ..Default::default()
+    };
+    assert_ne!(ob, relayed);
+    assert!(ob.approx_eq(&relayed, two_minutes));
+    assert!(ob.approx_eq(&relayed, ApproxOpts::default()));
+    let exact = ApproxOpts {
+        position: Angle::with_decimal_degrees(0.0),
+        ..Default::default()
+    };
+    assert!(!ob.approx_eq(&relayed, exact));
+
+    // Rounded a tenth differently, or reported a degree apart
+    let rounded = HDHALog::parse(&date, &line.replace("+201 +173 123041", "+202 +173 124041"));
+    assert!(ob.approx_eq(&rounded.unwrap(), ApproxOpts::default()));
+
+    let later = HDHALog::parse(&date, &line.replace("181830", "181900")).unwrap();
+    assert!(!ob.approx_eq(&later, two_minutes));
+    let no_temp = HDHALog::parse(&date, &line.replace("+201", "////")).unwrap();
+    assert!(!ob.approx_eq(&no_temp, two_minutes));
+    let flagged = HDHALog::parse(&date, &line.replace(" 002 00", " 002 03")).unwrap();
+    assert!(!ob.approx_eq(&flagged, two_minutes));
+}
+
+/// One whitespace-separated group of an HDOB data line.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Column {
+    pub name: &'static str,
+    pub width: usize,
+    /// Whether lines may end before this group. Optional groups follow all required ones.
+    pub optional: bool,
+}
+
+/// The groups of an HDOB data line, in transmission order.
+///
+/// The two optional sea-state groups after the quality flag are provisional: no bulletin
+/// carrying them has been seen yet, so their layout, a wave height in whole meters (`//` when
+/// missing) and a sea surface temperature formatted like the air temperature, is a guess that
+/// may change once samples are available. Lines whose trailing groups don't fit it are parsed
+/// as though the groups were absent.
+pub const HDOB_COLUMNS: &[Column] = &[
+    Column {
+        name: "time",
+        width: 6,
+        optional: false,
+    },
+    Column {
+        name: "lat",
+        width: 5,
+        optional: false,
+    },
+    Column {
+        name: "lon",
+        width: 6,
+        optional: false,
+    },
+    Column {
+        name: "pressure",
+        width: 4,
+        optional: false,
+    },
+    Column {
+        name: "height",
+        width: 5,
+        optional: false,
+    },
+    Column {
+        name: "esp",
+        width: 4,
+        optional: false,
+    },
+    Column {
+        name: "temp",
+        width: 4,
+        optional: false,
+    },
+    Column {
+        name: "dewpoint",
+        width: 4,
+        optional: false,
+    },
+    Column {
+        name: "wind",
+        width: 6,
+        optional: false,
+    },
+    Column {
+        name: "peak_wind",
+        width: 3,
+        optional: false,
+    },
+    Column {
+        name: "sfmr",
+        width: 3,
+        optional: false,
+    },
+    Column {
+        name: "rain_rate",
+        width: 3,
+        optional: false,
+    },
+    Column {
+        name: "quality",
+        width: 2,
+        optional: false,
+    },
+    Column {
+        name: "wave_height",
+        width: 2,
+        optional: true,
+    },
+    Column {
+        name: "sst",
+        width: 4,
+        optional: true,
+    },
+];
+
+/// Applies `policy` to runs of consecutive observations with the same time.
+fn dedup_times(obs: Vec<HDHALog>, policy: DuplicateTimes) -> Result<Vec<HDHALog>, ParseError> {
+    let repeated = |i: usize| {
+        (i > 0 && obs[i - 1].time == obs[i].time)
+            || (i + 1 < obs.len() && obs[i + 1].time == obs[i].time)
+    };
+    match policy {
+        DuplicateTimes::Error => match obs.windows(2).find(|pair| pair[0].time == pair[1].time) {
+            Some(pair) => Err(ParseError::DuplicateTime(pair[0].time)),
+            None => Ok(obs),
+        },
+        DuplicateTimes::DropAll => {
+            let keep: Vec<bool> = (0..obs.len()).map(|i| !repeated(i)).collect();
+            Ok(obs
+                .into_iter()
+                .zip(keep)
+                .filter_map(|(ob, keep)| keep.then_some(ob))
+                .collect())
+        }
+        DuplicateTimes::KeepFirst => {
+            let mut obs = obs;
+            obs.dedup_by_key(|ob| ob.time);
+            Ok(obs)
+        }
+    }
+}
+
+#[test]
+fn test_duplicate_times() {
+    let bulletin = "URNT15 KNHC 031829
+AF307 0606A EARL               HDOB 15 20220903
+181730 2006N 06139W 9249 00791 0114 +210 +174 116040 041 020 001 00
+181800 2006N 06141W 9247 00793 0115 +210 +173 121040 041 021 002 00
+181800 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00
+181830 2006N 06143W 9242 00793 0115 +208 +172 120042 043 021 000 00
+$$";
+    let parse = |duplicate_times| {
+        HDOBMessage::parse_with(
+            bulletin,
+            &ParseOptions {
+                duplicate_times,
+                ..Default::default()
+            },
+        )
+    };
+    let times = |message: HDOBMessage| {
+        message
+            .obs
+            .iter()
+            .map(|ob| ob.time.format("%H%M%S").to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let first = parse(DuplicateTimes::KeepFirst).unwrap();
+    assert_eq!(
+        Pressure::with_microbars(924_700),
+        first.obs[1].aircraft_pressure
+    );
+    assert_eq!(vec!["181730", "181800", "181830"], times(first));
+    assert_eq!(
+        vec!["181730", "181830"],
+        times(parse(DuplicateTimes::DropAll).unwrap())
+    );
+    let time = NaiveDate::from_ymd_opt(2022, 9, 3)
+        .and_then(|date| date.and_hms_opt(18, 18, 0))
+        .unwrap()
+        .and_utc();
+    assert_eq!(
+        Some(ParseError::DuplicateTime(time)),
+        parse(DuplicateTimes::Error).err()
+    );
+}
+
+/// Splits a data line into one token per entry of [`HDOB_COLUMNS`], stopping early if the line
+/// ends where an optional column may begin.
+///
+/// Groups are normally separated by single spaces, but some relays rewrite lines with commas or
+/// semicolons between groups instead; those delimiters are recognized, with any spaces around
+/// them ignored.
+fn split_columns(line: &str) -> Result<Vec<&str>, ParseError> {
+    let delimiter = [',', ';']
+        .into_iter()
+        .find(|d| line.contains(*d))
+        .unwrap_or(' ');
+    let mut tokens = line.split(delimiter).map(str::trim);
+    let mut columns = vec![];
+    for column in HDOB_COLUMNS {
+        match tokens.next() {
+            None | Some("") if column.optional => break,
+            Some(token) => columns.push(token),
+            None => return Err(ParseError::Missing(column.name)),
+        }
+    }
+    Ok(columns)
+}
+
+impl MetObservation for HDHALog {
+    fn time(&self) -> DateTime<Utc> {
+        self.time
+    }
+
+    fn location(&self) -> Coordinate {
+        self.location
+    }
+
+    /// The extrapolated surface pressure; observations above 550 mb report a D-value instead
+    /// and have none.
+    fn surface_pressure(&self) -> Option<Pressure> {
+        match self.surface_pressure {
+            Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(p)) => Some(p),
+            _ => None,
+        }
+    }
+
+    /// The 30-second average flight-level wind.
+    fn wind(&self) -> Option<Wind> {
+        self.wind
+    }
+
+    /// The flight-level air temperature.
+    fn temperature(&self) -> Option<Temperature> {
+        self.temp
+    }
+}
+
+#[test]
+fn test_met_observation() {
+    fn check(ob: &impl MetObservation) -> (Option<Pressure>, Option<Wind>, Option<Temperature>) {
+        (ob.surface_pressure(), ob.wind(), ob.temperature())
+    }
+
+    let date = NaiveDate::from_ymd_opt(2022, 9, 1).unwrap();
+    let line = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00";
+    let ob = HDHALog::parse(&date, line).unwrap();
+    assert_eq!(
+        date.and_hms_opt(18, 18, 30).unwrap().and_utc(),
+        MetObservation::time(&ob)
+    );
+    assert_eq!(ob.location, MetObservation::location(&ob));
+    assert_eq!(
+        (
+            Some(Pressure::with_microbars(1_011_500)),
+            Some(Wind::from_degrees_knots(123.0, 41)),
+            Some(Temperature::with_millicelsius(20_100)),
+        ),
+        check(&ob)
+    );
+
+    let line = "155100 2601N 09348W 3926 07749 0432 -173 //// 222015 015 /// /// 05";
+    let high = HDHALog::parse(&date, line).unwrap();
+    assert_eq!(None, MetObservation::surface_pressure(&high));
+}
+
+/// The raw tokens of a data line, one per entry of [`HDOB_COLUMNS`] present on it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RawFields {
+    tokens: Vec<String>,
+}
+
+impl RawFields {
+    /// Splits a data line into its columns without interpreting them, so this succeeds for
+    /// lines that fail to parse as long as no column is missing.
+    pub fn from_line(line: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            tokens: split_columns(line)?
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+
+    /// The raw token for the named column.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.iter()
+            .find(|(column, _)| column.name == name)
+            .map(|(_, token)| token)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static Column, &str)> {
+        HDOB_COLUMNS
+            .iter()
+            .zip(self.tokens.iter().map(String::as_str))
+    }
+}
+
+impl Display for RawFields {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.tokens.join(" "))
+    }
+}
+
+#[test]
+fn test_parse_debug() {
+    const LINE: &str = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00";
+    let date = NaiveDate::from_ymd_opt(2022, 9, 1).unwrap();
+
+    let (ob, raw) = HDHALog::parse_debug(&date, LINE).unwrap();
+    assert_eq!(HDHALog::parse(&date, LINE).unwrap(), ob);
+    assert_eq!(LINE, raw.to_string());
+    assert_eq!(Some("0115"), raw.get("esp"));
+    assert_eq!(None, raw.get("bogus"));
+
+    let bad = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 71";
+    assert!(HDHALog::parse_debug(&date, bad).is_err());
+    assert_eq!(
+        Some("71"),
+        RawFields::from_line(bad).unwrap().get("quality")
+    );
+}
+
+#[test]
+fn test_hdob_columns() {
+    const LINE: &str = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00";
+
+    let required: Vec<&Column> = HDOB_COLUMNS.iter().filter(|c| !c.optional).collect();
+    let width: usize = required.iter().map(|c| c.width).sum();
+    assert_eq!(LINE.len(), width + required.len() - 1);
+    // Optional columns only ever follow the required ones
+    assert!(HDOB_COLUMNS[required.len()..].iter().all(|c| c.optional));
+
+    let tokens = split_columns(LINE).unwrap();
+    assert_eq!(required.len(), tokens.len());
+    for (column, token) in HDOB_COLUMNS.iter().zip(tokens) {
+        assert_eq!(column.width, token.len(), "{}", column.name);
+    }
+
+    let extended = format!("{} 02 +285", LINE);
+    let tokens = split_columns(&extended).unwrap();
+    assert_eq!(HDOB_COLUMNS.len(), tokens.len());
+    for (column, token) in HDOB_COLUMNS.iter().zip(tokens) {
+        assert_eq!(column.width, token.len(), "{}", column.name);
+    }
+}
+
+/// Parses a single observation line without a bulletin date.
+///
+/// An HDOB line carries only the time of day; the date lives in the bulletin's mission header.
+/// Lines parsed this way are stamped with the Unix epoch (1970-01-01) as a sentinel date, so
+/// their `time` is only meaningful as a time of day. Use [`HDHALog::parse`] or the
+/// `TryFrom<(&NaiveDate, &str)>` impl when the date is known.
+impl FromStr for HDHALog {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        Self::parse(&NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(), line)
+    }
+}
+
+impl TryFrom<(&NaiveDate, &str)> for HDHALog {
+    type Error = ParseError;
+
+    fn try_from((date, line): (&NaiveDate, &str)) -> Result<Self, Self::Error> {
+        Self::parse(date, line)
+    }
+}
+
+#[test]
+fn test_parse_hdha_traits() {
+    const LINE: &str = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00";
+
+    let expected = HDHALog::parse(&NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(), LINE);
+    assert_eq!(expected, LINE.parse::<HDHALog>());
+
+    let date = NaiveDate::from_ymd_opt(2022, 9, 1).unwrap();
+    assert_eq!(
+        HDHALog::parse(&date, LINE),
+        HDHALog::try_from((&date, LINE))
+    );
+
+    assert_eq!(
+        Err(ParseError::Missing("quality")),
+        "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002".parse::<HDHALog>()
+    );
+}
+
+#[test]
+fn test_parse_hdob() {
+    let earl1 = include_str!("../testdata/hdob/20220905-31-HDOB-EARL-0906A-NOAA2.txt");
+    let _ = HDOBMessage::parse(earl1).unwrap();
+    //println!("{:#?}", attempt);
+
+    let earl2 = include_str!("../testdata/hdob/20220905-09-HDOB-EARL-1006A-AF308.txt");
+    let _ = HDOBMessage::parse(earl2).unwrap();
+    //println!("{:#?}", attempt);
+
+    let earl3 = include_str!("../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt");
+    let _ = HDOBMessage::parse(earl3).unwrap();
+    //println!("{:#?}", attempt)
+
+    let kay1 = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+    let attempt = HDOBMessage::parse(kay1).unwrap();
+    println!("{:#?}", attempt);
+}
+
+/// Gathers the lines of a stream of bulletins into one bulletin at a time.
+#[derive(Default)]
+struct BulletinSplitter {
+    text: String,
+}
+
+impl BulletinSplitter {
+    /// Adds a line, returning the bulletin it completes, if any.
+    fn push(&mut self, line: &str) -> Option<String> {
+        self.text.push_str(line);
+        self.text.push('\n');
+        if line.trim() == "$$" {
+            Some(std::mem::take(&mut self.text))
+        } else {
+            None
+        }
+    }
+
+    /// Returns whatever followed the last complete bulletin, unless it is only blank lines or
+    /// the `;` end-of-message marker.
+    fn finish(&mut self) -> Option<String> {
+        let text = std::mem::take(&mut self.text);
+        text.chars()
+            .any(|c| c.is_ascii_alphanumeric())
+            .then_some(text)
+    }
+}
+
+#[test]
+fn test_from_reader() {
+    let af307 = include_str!("../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt");
+    let kay = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+    // The NOAA2 bulletin has no $$, so it runs to the end of the input
+    let noaa2 = include_str!("../testdata/hdob/20220905-31-HDOB-EARL-0906A-NOAA2.txt");
+    let feed = format!("{}\n{}\n{}", af307, kay, noaa2);
+
+    let messages: Vec<HDOBMessage> = HDOBMessage::from_reader(feed.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let missions: Vec<&str> = messages.iter().map(|m| m.mission_id.as_str()).collect();
+    assert_eq!(
+        vec!["AF307 0606A EARL", "AF309 0112E KAY", "NOAA2 0906A EARL"],
+        missions
+    );
+    assert_eq!(HDOBMessage::parse(kay).unwrap().obs, messages[1].obs);
+
+    let trailing = format!("{}\n{}", af307, kay);
+    assert_eq!(2, HDOBMessage::from_reader(trailing.as_bytes()).count());
+
+    let corrupt = format!("{}\n{}", af307.replace("2001N", "20O1N"), kay);
+    let results: Vec<_> = HDOBMessage::from_reader(corrupt.as_bytes()).collect();
+    assert_eq!(2, results.len());
+    assert!(results[0].is_err());
+    assert!(results[1].is_ok());
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn test_stream() {
+    use futures_util::StreamExt;
+
+    let af307 = include_str!("../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt");
+    let kay = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+    let feed = format!("{}\n{}", af307, kay);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let messages: Vec<Result<HDOBMessage, ParseError>> =
+        runtime.block_on(HDOBMessage::stream(tokio::io::BufReader::new(feed.as_bytes())).collect());
+    assert_eq!(2, messages.len());
+    assert_eq!("AF307 0606A EARL", messages[0].as_ref().unwrap().mission_id);
+    assert_eq!(
+        HDOBMessage::parse(kay).unwrap().obs,
+        messages[1].as_ref().unwrap().obs
+    );
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_parse_hdob_gz() {
+    let earl = include_str!("../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt");
+    let earl_gz = include_bytes!("../testdata/hdob-gz/20220903-15-HDOB-EARL-0606A-AF307.txt.gz");
+    let expected = HDOBMessage::parse(earl).unwrap();
+    let attempt = HDOBMessage::parse_gz(&earl_gz[..]).unwrap();
+    assert_eq!(expected.mission_id, attempt.mission_id);
+    assert_eq!(expected.obs, attempt.obs);
+
+    let attempt = HDOBMessage::parse_gz(earl.as_bytes());
+    assert!(matches!(attempt, Err(ParseError::Io { .. })));
+}
+
+#[test]
+fn test_parse_dir() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata");
+    let results: Vec<_> = HDOBMessage::parse_dir(&dir.join("hdob")).unwrap().collect();
+    let names: Vec<_> = results
+        .iter()
+        .map(|(path, _)| path.file_name().unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(
+        vec![
+            "20220903-15-HDOB-EARL-0606A-AF307.txt",
+            "20220905-09-HDOB-EARL-1006A-AF308.txt",
+            "20220905-12-HDOB-KAY-0112E-AF309.txt",
+            "20220905-31-HDOB-EARL-0906A-NOAA2.txt",
+        ],
+        names
+    );
+    let missions: Vec<_> = results
+        .iter()
+        .map(|(_, result)| result.as_ref().unwrap().mission_id.as_str())
+        .collect();
+    assert_eq!(
+        vec![
+            "AF307 0606A EARL",
+            "AF308 1006A EARL",
+            "AF309 0112E KAY",
+            "NOAA2 0906A EARL"
+        ],
+        missions
+    );
+
+    // The testdata directory itself holds only subdirectories
+    assert_eq!(0, HDOBMessage::parse_dir(&dir).unwrap().count());
+    assert!(matches!(
+        HDOBMessage::parse_dir(&dir.join("missing")),
+        Err(ParseError::Io { .. })
+    ));
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_parse_dir_gz() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/hdob-gz");
+    let results: Vec<_> = HDOBMessage::parse_dir(&dir).unwrap().collect();
+    assert_eq!(1, results.len());
+    assert_eq!(
+        "AF307 0606A EARL",
+        results[0].1.as_ref().unwrap().mission_id
+    );
+}
 
-        Self {
-            header,
-            mission_id,
-            obs_number,
-            date,
-            obs,
+#[cfg(feature = "tracing")]
+#[test]
+fn test_parse_tracing_events() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tracing::{span, Event, Level, Metadata, Subscriber};
+
+    struct CountEvents {
+        warnings: Arc<AtomicUsize>,
+        debug: Arc<AtomicUsize>,
+    }
+
+    impl Subscriber for CountEvents {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
         }
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+        fn event(&self, event: &Event<'_>) {
+            match *event.metadata().level() {
+                Level::WARN => self.warnings.fetch_add(1, Ordering::SeqCst),
+                Level::DEBUG => self.debug.fetch_add(1, Ordering::SeqCst),
+                _ => 0,
+            };
+        }
+        fn enter(&self, _: &span::Id) {}
+        fn exit(&self, _: &span::Id) {}
     }
+
+    let warnings = Arc::new(AtomicUsize::new(0));
+    let debug = Arc::new(AtomicUsize::new(0));
+    let subscriber = CountEvents {
+        warnings: warnings.clone(),
+        debug: debug.clone(),
+    };
+    let text = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+    let message =
+        tracing::subscriber::with_default(subscriber, || HDOBMessage::parse(text)).unwrap();
+
+    let questionable = message
+        .obs
+        .iter()
+        .filter(|ob| {
+            ob.latlon_questionable
+                || ob.altitude_or_pressure_questionable
+                || ob.temp_or_dewpoint_questionable
+                || ob.winds_questionable
+                || ob.sfmr_questionable
+        })
+        .count();
+    assert!(questionable > 0);
+    assert_eq!(questionable, warnings.load(Ordering::SeqCst));
+    // Every observation at this altitude lacks SFMR and rain rate
+    assert_eq!(message.obs.len(), debug.load(Ordering::SeqCst));
 }
 
-#[derive(Debug)]
-pub struct HDHALog {
-    pub time: DateTime<Utc>,
-    pub location: Coordinate,
-    pub aircraft_pressure: Pressure,
-    pub height: Altitude,
-    pub surface_pressure: Option<ExtrapolatedSurfacePressure>,
-    pub temp: Option<Temperature>,
-    pub dewpoint: Option<Temperature>,
-    pub wind: Option<Wind>,
-    pub peak_wind_speed: Option<Speed>,
-    pub peak_sfmr_speed: Option<Speed>,
-    pub rain_rate: Option<RainRate>,
-    pub latlon_questionable: bool,
-    pub altitude_or_pressure_questionable: bool,
-    pub temp_or_dewpoint_questionable: bool,
-    pub winds_questionable: bool,
-    pub sfmr_questionable: bool,
+#[test]
+fn test_parse_hdob_invalid_date() {
+    let kay = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+    let attempt = HDOBMessage::parse(&kay.replace("HDOB 12 20220905", "HDOB 12 20221305"));
+    assert_eq!(
+        Err(ParseError::invalid("date", "20221305")),
+        attempt.map(|_| ())
+    );
 }
 
-impl HDHALog {
-    pub fn parse(date: &Date<Utc>, line: &str) -> Self {
-        let mut cols = line.split(" ");
+#[test]
+fn test_parse_lenient() {
+    let bulletin = "URNT15 KNHC 031829
+AF307 0606A EARL               HDOB 15 20220903
+181730 2006N 06139W 9249 00791 0114 +210 +174 116040 041 020 001 00
+181800 2006N 06141W 9247 00793 0115 +210 +173 121040 041 021 002 03
+181830 2006N 06143W 9242 00793 0117 +204 +172 120042 043 021 999 00
+$$";
+    let (message, warnings) = HDOBMessage::parse_lenient(bulletin);
+    assert_eq!(3, message.obs.len());
+    assert_eq!(
+        vec![
+            Warning::Questionable {
+                time: message.obs[1].time,
+                flags: message.obs[1].quality_flags(),
+            },
+            Warning::Sanity {
+                time: message.obs[2].time,
+                violation: SanityViolation::ImplausibleRainRate(RainRate::with_mm_per_hr(999)),
+            },
+        ],
+        warnings
+    );
 
-        let time = parse_hhmmss(date, cols.next().expect("Missing time"));
-        let location = parse_latlon(
-            cols.next().expect("Missing lat"),
-            cols.next().expect("Missing lon"),
-        );
-        let aircraft_pressure = parse_aircraft_pressure(cols.next().expect("Missing pressure"));
-        let height = Altitude::with_meters(cols.next().expect("Missing altitude").parse().unwrap());
-        let surface_pressure =
-            parse_extrapolated_sfc_pressure(aircraft_pressure, cols.next().expect("Missing ESP"));
-        let temp = parse_temperature(cols.next().expect("Missing temp"));
-        let dewpoint = parse_temperature(cols.next().expect("Missing dewpoint"));
-        let wind = parse_wind(cols.next().expect("Missing wind dir."));
-        let peak_wind_speed = parse_speed(cols.next().expect("Missing gusts"));
-        let peak_sfmr_speed = parse_speed(cols.next().expect("Missing sfmr"));
-        let rain_rate = parse_rain_rate(cols.next().expect("Missing rain rate"));
-
-        let quality = cols.next().expect("Missing quality").parse::<u8>().unwrap();
-        let (latlon_questionable, altitude_or_pressure_questionable) = match quality / 10 {
-            0 => (false, false),
-            1 => (true, false),
-            2 => (false, true),
-            3 => (true, true),
-            x => panic!("Unexpected pos quality: {}", x),
-        };
+    // A garbled line is skipped, leaving a gap
+    let garbled = bulletin.replace("181800 2006N", "181800 20O6N");
+    let (message, warnings) = HDOBMessage::parse_lenient(&garbled);
+    assert_eq!(2, message.obs.len());
+    assert_eq!(
+        Warning::SkippedLine {
+            line: 4,
+            error: ParseError::invalid("lat", "20O6N"),
+        },
+        warnings[0]
+    );
+    assert_eq!(
+        Some(&Warning::Gap {
+            from: message.obs[0].time,
+            to: message.obs[1].time,
+        }),
+        warnings.last()
+    );
+    assert!(HDOBMessage::parse(&garbled).is_err());
 
-        let (temp_or_dewpoint_questionable, winds_questionable, sfmr_questionable) =
-            match quality % 10 {
-                0 => (false, false, false),
-                1 => (true, false, false),
-                2 => (false, true, false),
-                3 => (false, false, true),
-                4 => (true, true, false),
-                5 => (true, false, true),
-                6 => (false, true, true),
-                9 => (true, true, true),
-                x => panic!("Unexpected met quality: {}", x),
-            };
+    // With no usable data lines, the skipped lines are still reported
+    let all_garbled = garbled.replace("2006N", "20O6N");
+    let (message, warnings) = HDOBMessage::parse_lenient(&all_garbled);
+    assert!(message.obs.is_empty());
+    assert_eq!(4, warnings.len());
+    assert!(matches!(warnings[0], Warning::SkippedLine { line: 3, .. }));
+    assert_eq!(Warning::Unparsable(ParseError::Empty), warnings[3]);
 
-        HDHALog {
-            time,
-            location,
-            aircraft_pressure,
-            height,
-            surface_pressure,
-            temp,
-            dewpoint,
-            wind,
-            peak_wind_speed,
-            peak_sfmr_speed,
-            rain_rate,
-            latlon_questionable,
-            altitude_or_pressure_questionable,
-            temp_or_dewpoint_questionable,
-            winds_questionable,
-            sfmr_questionable,
-        }
-    }
+    let (message, warnings) = HDOBMessage::parse_lenient("not a bulletin");
+    assert!(message.obs.is_empty());
+    assert_eq!("", message.mission_id);
+    assert_eq!(
+        vec![Warning::Unparsable(ParseError::Missing("header"))],
+        warnings
+    );
 }
 
 #[test]
-fn test_parse_hdob() {
-    let earl1 = include_str!("../testdata/hdob/20220905-31-HDOB-EARL-0906A-NOAA2.txt");
-    let _ = HDOBMessage::parse(earl1);
-    //println!("{:#?}", attempt);
+fn test_parse_hdob_stripped_zeros() {
+    let kay = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+    let stripped = kay.replace("HDOB 12 20220905", "HDOB 9 20220905");
+    let message = HDOBMessage::parse(&stripped).unwrap();
+    assert_eq!(9, message.obs_number);
+    assert_eq!(NaiveDate::from_ymd_opt(2022, 9, 5), Some(message.date));
 
-    let earl2 = include_str!("../testdata/hdob/20220905-09-HDOB-EARL-1006A-AF308.txt");
-    let _ = HDOBMessage::parse(earl2);
-    //println!("{:#?}", attempt);
+    let attempt = HDOBMessage::parse(&kay.replace("HDOB 12 20220905", "HDOB 12 202295"));
+    assert_eq!(
+        Err(ParseError::invalid("date", "202295")),
+        attempt.map(|_| ())
+    );
+}
 
-    let earl3 = include_str!("../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt");
-    let _ = HDOBMessage::parse(earl3);
-    //println!("{:#?}", attempt)
+#[test]
+fn test_parse_hdob_framing() {
+    let kay = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+    let expected = HDOBMessage::parse(kay).unwrap();
 
-    let kay1 = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
-    let attempt = HDOBMessage::parse(kay1);
-    println!("{:#?}", attempt);
+    let crlf = format!("\u{feff}\x01\r\n\r\n{}\x03", kay.replace('\n', "\r\n"));
+    let attempt = HDOBMessage::parse(&crlf).unwrap();
+    assert_eq!(expected.header, attempt.header);
+    assert_eq!(expected.mission_id, attempt.mission_id);
+    assert_eq!(expected.obs, attempt.obs);
+    // The framing characters are dropped, but the blank lines still count
+    assert_eq!(expected.obs[0].source_line + 2, attempt.obs[0].source_line);
+
+    let unsequenced = kay.split_once('\n').unwrap().1;
+    let attempt = HDOBMessage::parse(unsequenced).unwrap();
+    assert_eq!(expected.obs, attempt.obs);
+    assert_eq!(expected.obs[0].source_line - 1, attempt.obs[0].source_line);
+}
+
+#[test]
+fn test_source_line() {
+    let af307 = include_str!("../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt");
+    let message = HDOBMessage::parse(af307).unwrap();
+    // After the sequence number, WMO header, and mission header
+    assert_eq!(4, message.obs[0].source_line());
+    assert_eq!(23, message.obs[19].source_line());
+
+    // The line number is provenance, not part of the observation
+    let alone = HDHALog::parse(&message.date, af307.lines().nth(3).unwrap()).unwrap();
+    assert_eq!(1, alone.source_line());
+    assert_eq!(message.obs[0], alone);
+
+    let line = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00";
+    assert_eq!(1, line.parse::<HDHALog>().unwrap().source_line());
+}
+
+#[test]
+fn test_parse_hdob_empty() {
+    assert_eq!(Err(ParseError::Empty), HDOBMessage::parse("").map(|_| ()));
+    assert_eq!(
+        Err(ParseError::Empty),
+        HDOBMessage::parse(" \r\n\n").map(|_| ())
+    );
+    assert_eq!(
+        Err(ParseError::Empty),
+        HDOBMessage::parse("000\nURNT15 KNHC 051606\n").map(|_| ())
+    );
+    assert_eq!(
+        Err(ParseError::Empty),
+        HDOBMessage::parse("URNT15 KNHC 051606\nAF309 0112E KAY HDOB 12 20220905\n$$\n;")
+            .map(|_| ())
+    );
+    assert_eq!(
+        Err(ParseError::Missing("header")),
+        HDOBMessage::parse("not a bulletin").map(|_| ())
+    );
+}
+
+#[test]
+fn test_parse_hdob_mission_header_position() {
+    let kay = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+    let expected = HDOBMessage::parse(kay).unwrap();
+    let insert = |extra: &str| {
+        let mut lines: Vec<&str> = kay.lines().collect();
+        let at = lines.iter().position(|l| l.contains(" HDOB ")).unwrap();
+        lines.insert(at, extra);
+        lines.join("\n")
+    };
+
+    for extra in ["", "HDOB12"] {
+        let message = HDOBMessage::parse(&insert(extra)).unwrap();
+        assert_eq!(expected.mission_id, message.mission_id);
+        assert_eq!(expected.obs_number, message.obs_number);
+        assert_eq!(expected.obs, message.obs);
+    }
+
+    // Reported against the line where the mission header was expected
+    let without = kay
+        .lines()
+        .filter(|l| !l.contains(" HDOB "))
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_eq!(
+        Some(ParseError::invalid(
+            "mission header",
+            "155100 2601N 09348W 3926 07749 0432 -173 //// 222015 015 /// /// 05"
+        )),
+        HDOBMessage::parse(&without).err()
+    );
+    let malformed = kay.replace("HDOB 12", "HDOB XX");
+    let line = malformed.lines().find(|l| l.starts_with("AF309")).unwrap();
+    assert_eq!(
+        Some(ParseError::invalid("mission header", line.trim())),
+        HDOBMessage::parse(&malformed).err()
+    );
+}
+
+#[test]
+fn test_parse_hdob_obs_count() {
+    let options = ParseOptions {
+        expected_obs_count: Some(20),
+        ..Default::default()
+    };
+
+    let earl = include_str!("../testdata/hdob/20220903-15-HDOB-EARL-0606A-AF307.txt");
+    assert!(HDOBMessage::parse_with(earl, &options).is_ok());
+
+    let truncated = earl.lines().take(17).collect::<Vec<_>>().join("\n");
+    assert_eq!(
+        Err(ParseError::ObsCountMismatch {
+            expected: 20,
+            found: 14
+        }),
+        HDOBMessage::parse_with(&truncated, &options).map(|_| ())
+    );
+    assert!(HDOBMessage::parse(&truncated).is_ok());
 }
 
 #[test]
 fn test_parse_hdha() {
-    let date = Utc.ymd(2022, 09, 01);
+    let date = NaiveDate::from_ymd_opt(2022, 9, 1).unwrap();
     const LINE1: &str = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00";
 
-    let attempt = HDHALog::parse(&date, LINE1);
+    let attempt = HDHALog::parse(&date, LINE1).unwrap();
     println!("{:#?}", attempt);
 
     const LINES2: &str = "135600 1821N 06526W 7752 02317 0126 +145 +051 234022 023 /// /// 03
@@ -191,74 +5097,234 @@ fn test_parse_hdha() {
 140430 1755N 06504W 9278 00779 0129 +238 +210 214027 027 /// /// 03
 140500 1753N 06502W 9278 00779 0132 +238 +213 215027 028 /// /// 03
 140530 1752N 06501W 9278 00779 0131 +239 +213 213027 028 /// /// 03";
-    for hdha in LINES2.lines().map(|it| HDHALog::parse(&date, it)) {
+    for hdha in LINES2.lines().map(|it| HDHALog::parse(&date, it).unwrap()) {
         println!("{:?}", hdha)
     }
 }
 
+#[test]
+fn test_parse_hdha_quality_mode() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+    let line = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 77";
+    assert_eq!(
+        Err(ParseError::invalid("quality", "77")),
+        HDHALog::parse(&date, line)
+    );
+
+    let options = ParseOptions {
+        quality_mode: QualityMode::Lenient,
+        ..Default::default()
+    };
+    let ob = HDHALog::parse_with(&date, line, &options).unwrap();
+    assert!(ob.latlon_questionable);
+    assert!(ob.altitude_or_pressure_questionable);
+    assert!(ob.temp_or_dewpoint_questionable);
+    assert!(ob.winds_questionable);
+    assert!(ob.sfmr_questionable);
+
+    // Known digits are still decoded normally
+    let line = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 07";
+    let ob = HDHALog::parse_with(&date, line, &options).unwrap();
+    assert!(!ob.latlon_questionable);
+    assert!(!ob.altitude_or_pressure_questionable);
+    assert!(ob.sfmr_questionable);
+}
+
+#[test]
+fn test_parse_hdha_delimiters() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+    let line = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00";
+    let expected = HDHALog::parse(&date, line).unwrap();
+
+    for delimited in [
+        line.replace(' ', ","),
+        line.replace(' ', ", "),
+        line.replace(' ', ";"),
+    ] {
+        assert_eq!(Ok(&expected), HDHALog::parse(&date, &delimited).as_ref());
+    }
+    assert_eq!(
+        Err(ParseError::Missing("quality")),
+        HDHALog::parse(
+            &date,
+            "181830,2006N,06141W,9236,00794,0115,+201,+173,123041,041,021,002"
+        )
+    );
+}
+
+#[test]
+fn test_encode_hdha() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 5).unwrap();
+    let options = EncodeOptions::default();
+    for line in [
+        "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00",
+        "155930 2531N 09422W 3926 07748 0433 -169 -351 230012 013 /// /// 03",
+        "155100 2601N 09348W 3926 07749 0432 -173 //// 222015 015 /// /// 05",
+    ] {
+        let attempt = HDHALog::parse(&date, line).unwrap().to_hdob(&options);
+        assert_eq!(line, attempt);
+    }
+}
+
+/// How values carrying more precision than an HDOB group allows are collapsed when encoding.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Rounding {
+    /// Drop the excess precision, so parsed values re-encode exactly as transmitted.
+    #[default]
+    Truncate,
+    /// Round half up to the nearest representable value, for derived data such as interpolated pressures.
+    RoundHalfUp,
+}
+
+impl Rounding {
+    fn apply(&self, value: i32, divisor: i32) -> i32 {
+        match self {
+            Self::Truncate => value / divisor,
+            Self::RoundHalfUp => (value + divisor / 2).div_euclid(divisor),
+        }
+    }
+}
+
+/// Per-field tolerances for [`HDHALog::approx_eq`]. The defaults allow for rounding in the last
+/// transmitted digit of each group.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ApproxOpts {
+    /// Applies to latitude and longitude separately.
+    pub position: Angle,
+    /// Applies to the aircraft and extrapolated surface pressures.
+    pub pressure: Pressure,
+    /// Applies to the geopotential height, D-value, and wave height.
+    pub height: Altitude,
+    /// Applies to the temperature, dewpoint, and sea surface temperature.
+    pub temperature: Temperature,
+    /// Applies to every wind speed.
+    pub speed: Speed,
+    /// Applies to the flight-level wind direction.
+    pub direction: Angle,
+}
+
+impl Default for ApproxOpts {
+    fn default() -> Self {
+        Self {
+            position: Angle::with_degrees_minutes_seconds(0, 1, 0),
+            pressure: Pressure::with_microbars(100),
+            height: Altitude::with_meters(1),
+            temperature: Temperature::with_millikelvin(100),
+            speed: Speed::with_knots(1),
+            direction: Angle::with_degrees_minutes_seconds(1, 0, 0),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct EncodeOptions {
+    pub rounding: Rounding,
+    /// Whether [`HDOBMessage::to_bulletin`] rejects groups wider than the fixed widths of the
+    /// operational format.
+    pub strict_width: bool,
+}
+
+/// An internal inconsistency found by [`HDHALog::sanity_check`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SanityViolation {
+    /// The transmitted D-value disagrees with the one implied by height and aircraft pressure,
+    /// typically due to transposed digits in one of the groups.
+    DValueMismatch { reported: DValue, implied: DValue },
+    /// The SFMR rain rate exceeds [`SanityLimits::max_rain_rate`].
+    ImplausibleRainRate(RainRate),
+}
+
+/// Thresholds for [`HDHALog::sanity_check_with`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SanityLimits {
+    pub max_rain_rate: RainRate,
+}
+
+impl Default for SanityLimits {
+    fn default() -> Self {
+        Self {
+            max_rain_rate: RainRate::MAX_PLAUSIBLE,
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum ExtrapolatedSurfacePressure {
     ExtrapolatedPressure(Pressure),
     DValue(DValue),
 }
 
-fn parse_hhmmss<TZ: TimeZone>(date: &Date<TZ>, hhmmss: &str) -> DateTime<TZ> {
+fn parse_hhmmss(date: &NaiveDate, hhmmss: &str) -> Result<DateTime<Utc>, ParseError> {
     lazy_static! {
-        static ref RE: Regex = Regex::new(r"([0-9]{2})([0-9]{2})([0-9]{2})").unwrap();
+        static ref RE: Regex = Regex::new(r"^([0-9]{2})([0-9]{2})([0-9]{2})$").unwrap();
     }
 
-    let captures = RE.captures(hhmmss).unwrap();
+    let captures = RE
+        .captures(hhmmss)
+        .ok_or_else(|| ParseError::invalid("time", hhmmss))?;
     let hours = captures.get(1).unwrap().as_str().parse::<u32>().unwrap();
     let mins = captures.get(2).unwrap().as_str().parse::<u32>().unwrap();
     let secs = captures.get(3).unwrap().as_str().parse::<u32>().unwrap();
-    date.and_hms(hours, mins, secs)
+    date.and_hms_opt(hours, mins, secs)
+        .map(|t| t.and_utc())
+        .ok_or_else(|| ParseError::invalid("time", hhmmss))
 }
 
 #[test]
 fn test_parse_hms() {
-    let expected = Utc.ymd(2022, 09, 01).and_hms(18, 03, 09);
-    let attempt = parse_hhmmss(&Utc.ymd(2022, 09, 01), "180309");
-    assert_eq!(expected, attempt)
+    let date = NaiveDate::from_ymd_opt(2022, 9, 1).unwrap();
+    let expected = date.and_hms_opt(18, 3, 9).unwrap().and_utc();
+    let attempt = parse_hhmmss(&date, "180309");
+    assert_eq!(Ok(expected), attempt);
+
+    let attempt = parse_hhmmss(&date, "250309");
+    assert_eq!(Err(ParseError::invalid("time", "250309")), attempt);
 }
 
-fn parse_latlon(llllh: &str, nnnnnh: &str) -> Coordinate {
+fn parse_latlon(llllh: &str, nnnnnh: &str) -> Result<Coordinate, ParseError> {
     lazy_static! {
-        static ref RELAT: Regex = Regex::new(r"([0-9]{2})([0-9]{2})([NS])").unwrap();
-        static ref RELON: Regex = Regex::new(r"([0-9]{3})([0-9]{2})([EW])").unwrap();
+        static ref RELAT: Regex = Regex::new(r"([0-9]{2})([0-9]{2})([NSns])").unwrap();
+        static ref RELON: Regex = Regex::new(r"([0-9]{3})([0-9]{2})([EWew])").unwrap();
     }
 
-    let captures_lat = RELAT.captures(llllh).unwrap();
-    let captures_lon = RELON.captures(nnnnnh).unwrap();
+    if is_missing(llllh) {
+        return Err(ParseError::Missing("lat"));
+    }
+    if is_missing(nnnnnh) {
+        return Err(ParseError::Missing("lon"));
+    }
+    let captures_lat = RELAT
+        .captures(llllh)
+        .ok_or_else(|| ParseError::invalid("lat", llllh))?;
+    let captures_lon = RELON
+        .captures(nnnnnh)
+        .ok_or_else(|| ParseError::invalid("lon", nnnnnh))?;
 
+    // Some relays lowercase the hemisphere
     let hemi_lat = match captures_lat.get(3).unwrap().as_str() {
-        "N" => NORTH,
-        "S" => SOUTH,
-        _ => panic!(),
+        "N" | "n" => NORTH,
+        _ => SOUTH,
     };
     let hemi_lon = match captures_lon.get(3).unwrap().as_str() {
-        "E" => EAST,
-        "W" => WEST,
-        _ => panic!(),
+        "E" | "e" => EAST,
+        _ => WEST,
     };
 
-    Coordinate {
+    let angle = |captures: &regex::Captures, max: u32| {
+        let part = |i: usize| captures.get(i).unwrap().as_str().parse().unwrap();
+        Angle::try_with_degrees_minutes_seconds(part(1), part(2), 0)
+            .filter(|angle| *angle <= Angle::with_degrees_minutes_seconds(max, 0, 0))
+    };
+    Ok(Coordinate {
         latitude: Latitude {
-            angle: Angle::with_degrees_minutes_seconds(
-                captures_lat.get(1).unwrap().as_str().parse().unwrap(),
-                captures_lat.get(2).unwrap().as_str().parse().unwrap(),
-                0,
-            ),
+            angle: angle(&captures_lat, 90).ok_or_else(|| ParseError::invalid("lat", llllh))?,
             hemisphere: hemi_lat,
         },
         longitude: Longitude {
-            angle: Angle::with_degrees_minutes_seconds(
-                captures_lon.get(1).unwrap().as_str().parse().unwrap(),
-                captures_lon.get(2).unwrap().as_str().parse().unwrap(),
-                0,
-            ),
+            angle: angle(&captures_lon, 180).ok_or_else(|| ParseError::invalid("lon", nnnnnh))?,
             hemisphere: hemi_lon,
         },
-    }
+    })
 }
 
 #[test]
@@ -266,27 +5332,130 @@ fn test_parse_latlon() {
     // 2006N 06141W
     let expected = Coordinate {
         latitude: Latitude {
-            angle: Angle::with_degrees_minutes_seconds(20, 06, 0),
+            angle: Angle::with_degrees_minutes_seconds(20, 6, 0),
             hemisphere: NORTH,
         },
         longitude: Longitude {
-            angle: Angle::with_degrees_minutes_seconds(061, 41, 0),
+            angle: Angle::with_degrees_minutes_seconds(61, 41, 0),
             hemisphere: WEST,
         },
     };
     let attempt = parse_latlon("2006N", "06141W");
-    assert_eq!(expected, attempt);
+    assert_eq!(Ok(expected), attempt);
+    assert_eq!(Ok(expected), parse_latlon("2006n", "06141w"));
+    assert_eq!(
+        Err(ParseError::Missing("lat")),
+        parse_latlon("////", "06141W")
+    );
+    assert_eq!(
+        Err(ParseError::Missing("lon")),
+        parse_latlon("2006N", ".....")
+    );
+    assert_eq!(
+        Err(ParseError::invalid("lat", "2075N")),
+        parse_latlon("2075N", "06141W")
+    );
+    assert_eq!(
+        Err(ParseError::invalid("lat", "9100N")),
+        parse_latlon("9100N", "06141W")
+    );
+    assert_eq!(
+        Err(ParseError::invalid("lon", "18030W")),
+        parse_latlon("2006N", "18030W")
+    );
+    assert!(parse_latlon("9000S", "18000E").is_ok());
+}
+
+#[test]
+fn test_parse_all_missing_line() {
+    const LINE: &str = "183000 //// ///// //// ///// /// /// /// ////// /// /// /// 99";
+    assert_eq!(Err(ParseError::Missing("lat")), LINE.parse::<HDHALog>());
+
+    // A bulletin carrying one is rejected whole, or parsed around it leniently
+    let kay = include_str!("../testdata/hdob/20220905-12-HDOB-KAY-0112E-AF309.txt");
+    let mut lines: Vec<&str> = kay.lines().collect();
+    let at = lines.iter().position(|l| l.starts_with("$$")).unwrap();
+    lines.insert(at, LINE);
+    let bulletin = lines.join("\n");
+    assert!(HDOBMessage::parse(&bulletin).is_err());
+    let (message, warnings) = HDOBMessage::parse_lenient(&bulletin);
+    assert_eq!(
+        HDOBMessage::parse(kay).unwrap().obs.len(),
+        message.obs.len()
+    );
+    let skipped: Vec<&Warning> = warnings
+        .iter()
+        .filter(|w| matches!(w, Warning::SkippedLine { .. }))
+        .collect();
+    assert!(matches!(
+        skipped.as_slice(),
+        [Warning::SkippedLine {
+            error: ParseError::Missing("lat"),
+            ..
+        }]
+    ));
+}
+
+#[test]
+fn test_parse_missing_markers() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+    let slashes = "181830 2006N 06141W 9236 00794 //// //// //// ////// /// /// /// 00";
+    let dots = "181830 2006n 06141w 9236 00794 .... .... .... ...... ... ... ... 00";
+    let expected = HDHALog::parse(&date, slashes).unwrap();
+    assert_eq!(None, expected.surface_pressure);
+    assert_eq!(None, expected.temp);
+    assert_eq!(expected, HDHALog::parse(&date, dots).unwrap());
+}
+
+#[test]
+fn test_latlon_to_hdob() {
+    let coordinate = parse_latlon("2006N", "06141W").unwrap();
+    assert_eq!("2006N", coordinate.latitude.to_hdob());
+    assert_eq!("06141W", coordinate.longitude.to_hdob());
+
+    // Seconds are dropped, so positions survive the round trip to the minute
+    let precise = Coordinate {
+        latitude: Latitude {
+            angle: Angle::with_degrees_minutes_seconds(5, 9, 40),
+            hemisphere: SOUTH,
+        },
+        longitude: Longitude {
+            angle: Angle::with_degrees_minutes_seconds(170, 2, 59),
+            hemisphere: EAST,
+        },
+    };
+    assert_eq!("0509S", precise.latitude.to_hdob());
+    assert_eq!("17002E", precise.longitude.to_hdob());
+    let round_trip =
+        parse_latlon(&precise.latitude.to_hdob(), &precise.longitude.to_hdob()).unwrap();
+    assert!(round_trip.latitude.approx_eq(&precise.latitude, 1.0 / 60.0));
+    assert!(round_trip
+        .longitude
+        .approx_eq(&precise.longitude, 1.0 / 60.0));
+    assert_eq!(
+        Angle::with_degrees_minutes_seconds(170, 2, 0),
+        round_trip.longitude.angle
+    );
 }
 
-fn parse_aircraft_pressure(pppp: &str) -> Pressure {
-    let raw: i32 = pppp.parse().unwrap();
-    // Aircraft static air pressure, in tenths of mb with decimal omitted
-    if raw > 2000 {
+/// Parses a pressure group in tenths of mb with the decimal and any leading 1 omitted.
+///
+/// `2000`-`9999` are 200.0-999.9 mb and `0000`-`0999` are 1000.0-1099.9 mb. `1000`-`1999` could
+/// be 100.0-199.9 mb or 1100.0-1199.9 mb, neither of which is flown or observed, so they are
+/// rejected rather than guessed at.
+fn parse_aircraft_pressure(pppp: &str) -> Result<Pressure, ParseError> {
+    if is_missing(pppp) {
+        return Err(ParseError::Missing("pressure"));
+    }
+    let raw: i32 = pppp
+        .parse()
+        .map_err(|_| ParseError::invalid("pressure", pppp))?;
+    match raw {
         // leading 1 not dropped
-        Pressure::with_microbars(raw * 100)
-    } else {
+        2000..=9999 => Ok(Pressure::with_microbars(raw * 100)),
         // leading 1 dropped
-        Pressure::with_microbars((raw + 10000) * 100)
+        0..=999 => Ok(Pressure::with_microbars((raw + 10000) * 100)),
+        _ => Err(ParseError::invalid("pressure", pppp)),
     }
 }
 
@@ -294,38 +5463,59 @@ fn parse_aircraft_pressure(pppp: &str) -> Pressure {
 fn test_parse_aircraft_pressure() {
     let expected1 = Pressure::with_microbars(923_600);
     let attempt1 = parse_aircraft_pressure("9236");
-    assert_eq!(expected1, attempt1);
+    assert_eq!(Ok(expected1), attempt1);
 
     let expected2 = Pressure::with_microbars(1_023_400);
     let attempt2 = parse_aircraft_pressure("0234");
-    assert_eq!(expected2, attempt2);
+    assert_eq!(Ok(expected2), attempt2);
+
+    assert_eq!(
+        Ok(Pressure::with_microbars(200_000)),
+        parse_aircraft_pressure("2000")
+    );
+    assert_eq!(
+        Ok(Pressure::with_microbars(1_099_900)),
+        parse_aircraft_pressure("0999")
+    );
+    for ambiguous in ["1000", "1500", "1999"] {
+        assert_eq!(
+            Err(ParseError::invalid("pressure", ambiguous)),
+            parse_aircraft_pressure(ambiguous)
+        );
+    }
+    assert!(parse_aircraft_pressure("10234").is_err());
+    assert_eq!(
+        Err(ParseError::Missing("pressure")),
+        parse_aircraft_pressure("////")
+    );
 }
 
 fn parse_extrapolated_sfc_pressure(
     altitude: Pressure,
     xxxx: &str,
-) -> Option<ExtrapolatedSurfacePressure> {
-    if xxxx == MISSING {
-        None
+) -> Result<Option<ExtrapolatedSurfacePressure>, ParseError> {
+    if is_missing(xxxx) {
+        Ok(None)
     } else {
-        if altitude.millibars() < 550 {
-            // D-Value
-            let raw: i32 = xxxx.parse().unwrap();
+        if altitude < DVALUE_PRESSURE_THRESHOLD {
+            // D-Value. Negative values are sent with 5000 added, so 5001-9998 encode -1 to
+            // -4998 m; 5000 itself is an ordinary (if implausible) +5000 m.
+            let raw: i32 = xxxx.parse().map_err(|_| ParseError::invalid("esp", xxxx))?;
             if raw > 5000 {
                 // Negative D-value
-                Some(ExtrapolatedSurfacePressure::DValue(DValue::with_meters(
-                    -1 * (raw - 5000),
+                Ok(Some(ExtrapolatedSurfacePressure::DValue(
+                    DValue::with_meters(5000 - raw),
                 )))
             } else {
-                Some(ExtrapolatedSurfacePressure::DValue(DValue::with_meters(
-                    raw,
+                Ok(Some(ExtrapolatedSurfacePressure::DValue(
+                    DValue::with_meters(raw),
                 )))
             }
         } else {
             // Extrapolated surface pressure
-            Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(
-                parse_aircraft_pressure(xxxx),
-            ))
+            Ok(Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(
+                parse_aircraft_pressure(xxxx).map_err(|_| ParseError::invalid("esp", xxxx))?,
+            )))
         }
     }
 }
@@ -336,33 +5526,292 @@ fn test_parse_extrapolated_sfc_pressure() {
     let expected1 =
         ExtrapolatedSurfacePressure::ExtrapolatedPressure(Pressure::with_microbars(1_011_500));
     let attempt1 = parse_extrapolated_sfc_pressure(alt, "0115");
-    assert_eq!(Some(expected1), attempt1)
+    assert_eq!(Ok(Some(expected1)), attempt1)
 }
 
-fn parse_temperature(sttt: &str) -> Option<Temperature> {
-    sttt.parse()
-        .map(|mc: i32| Temperature::with_millicelsius(mc * 100))
-        .ok()
+#[test]
+fn test_parse_dvalue_boundary() {
+    let alt = Pressure::with_microbars(392_600);
+    let dvalue = |m| {
+        Ok(Some(ExtrapolatedSurfacePressure::DValue(
+            DValue::with_meters(m),
+        )))
+    };
+    assert_eq!(dvalue(4999), parse_extrapolated_sfc_pressure(alt, "4999"));
+    assert_eq!(dvalue(5000), parse_extrapolated_sfc_pressure(alt, "5000"));
+    assert_eq!(dvalue(-1), parse_extrapolated_sfc_pressure(alt, "5001"));
+    assert_eq!(dvalue(-4998), parse_extrapolated_sfc_pressure(alt, "9998"));
+
+    let below = Pressure::with_microbars(DVALUE_PRESSURE_THRESHOLD.microbars() - 100);
+    assert_eq!(
+        Pressure::with_millibars_f64(550.0),
+        DVALUE_PRESSURE_THRESHOLD
+    );
+    assert_eq!(dvalue(4999), parse_extrapolated_sfc_pressure(below, "4999"));
+    assert_eq!(
+        Ok(Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(
+            Pressure::with_microbars(499_900)
+        ))),
+        parse_extrapolated_sfc_pressure(DVALUE_PRESSURE_THRESHOLD, "4999")
+    );
+}
+
+#[test]
+fn test_parse_negative_dvalue() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 5).unwrap();
+    let line = "155100 2601N 09348W 3926 07749 5030 -173 //// 222015 015 /// /// 05";
+    let ob = HDHALog::parse(&date, line).unwrap();
+    let Some(ExtrapolatedSurfacePressure::DValue(dvalue)) = ob.surface_pressure else {
+        panic!("expected a D-value, found {:?}", ob.surface_pressure);
+    };
+    assert_eq!(DValue::with_meters(-30), dvalue);
+    assert!(dvalue.is_negative());
+    assert_eq!("DValue(-30 m)", format!("{:?}", dvalue));
+}
+
+/// Parses the optional significant wave height group: two digits of whole meters, or a missing
+/// marker.
+fn parse_wave_height(hh: &str) -> Result<Option<Altitude>, ParseError> {
+    if is_missing(hh) {
+        return Ok(None);
+    }
+    match hh.parse() {
+        Ok(meters) if hh.len() == 2 && hh.bytes().all(|b| b.is_ascii_digit()) => {
+            Ok(Some(Altitude::with_meters(meters)))
+        }
+        _ => Err(ParseError::invalid("wave height", hh)),
+    }
+}
+
+#[test]
+fn test_parse_sea_state() {
+    let date = NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+    let line = "181830 2006N 06141W 9236 00794 0115 +201 +173 123041 041 021 002 00 03 +287";
+    let ob = HDHALog::parse(&date, line).unwrap();
+    assert_eq!(Some(Altitude::with_meters(3)), ob.wave_height());
+    assert_eq!(Some(Temperature::with_millicelsius(28_700)), ob.sst());
+    assert_eq!(line, ob.to_hdob(&EncodeOptions::default()));
+
+    let plain = HDHALog::parse(&date, &line[..line.len() - 8]).unwrap();
+    assert_eq!((None, None), (plain.wave_height, plain.sst));
+    assert_eq!(
+        HDHALog {
+            wave_height: None,
+            sst: None,
+            ..ob.clone()
+        },
+        plain
+    );
+
+    let missing = HDHALog::parse(&date, &line.replace("03 +287", "// ////")).unwrap();
+    assert_eq!((None, None), (missing.wave_height, missing.sst));
+    let wave_only = HDHALog::parse(&date, &line.replace("03 +287", "//")).unwrap();
+    assert_eq!(None, wave_only.sst);
+
+    // Trailing groups that don't fit the provisional layout are ignored
+    for other in ["3 +287", "03 287", "XYZ"] {
+        let ob = HDHALog::parse(&date, &line.replace("03 +287", other)).unwrap();
+        assert_eq!(plain, ob, "{}", other);
+    }
+    let extra = HDHALog::parse(&date, &format!("{} 99", line)).unwrap();
+    assert_eq!(ob, extra);
+}
+
+/// Parses a temperature or dewpoint group: an explicit `+` or `-` followed by digits, or a
+/// missing marker.
+///
+/// Unlike the older RECCO and TEMP codes, HDOB never folds the sign into the digits (e.g. an
+/// odd tenths digit for negative), so an unsigned group is malformed rather than positive.
+fn parse_temperature(
+    field: &'static str,
+    sttt: &str,
+    scale: TemperatureScale,
+) -> Result<Option<Temperature>, ParseError> {
+    if is_missing(sttt) {
+        return Ok(None);
+    }
+    let millicelsius_per_unit = match scale {
+        TemperatureScale::Tenths => 100,
+        TemperatureScale::Whole => 1000,
+    };
+    let digits = sttt.strip_prefix(['+', '-']);
+    match digits {
+        Some(digits) if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) => sttt
+            .parse::<i32>()
+            .ok()
+            .and_then(|raw| raw.checked_mul(millicelsius_per_unit))
+            .and_then(Temperature::try_with_millicelsius)
+            .map(Some)
+            .ok_or_else(|| ParseError::invalid(field, sttt)),
+        _ => Err(ParseError::invalid(field, sttt)),
+    }
+}
+
+#[test]
+fn test_parse_temperature() {
+    assert_eq!(
+        Ok(Some(Temperature::with_millicelsius(2_000))),
+        parse_temperature("temp", "+20", TemperatureScale::Tenths)
+    );
+    assert_eq!(
+        Ok(Some(Temperature::with_millicelsius(20_000))),
+        parse_temperature("temp", "+20", TemperatureScale::Whole)
+    );
+    assert_eq!(
+        Ok(Some(Temperature::with_millicelsius(-17_300))),
+        parse_temperature("temp", "-173", TemperatureScale::Tenths)
+    );
+    assert_eq!(
+        Ok(None),
+        parse_temperature("dewpoint", "////", TemperatureScale::Tenths)
+    );
+
+    // The sign is mandatory
+    assert_eq!(
+        Err(ParseError::invalid("temp", "201")),
+        parse_temperature("temp", "201", TemperatureScale::Tenths)
+    );
+    assert_eq!(
+        Err(ParseError::invalid("dewpoint", "+1O3")),
+        parse_temperature("dewpoint", "+1O3", TemperatureScale::Tenths)
+    );
+    assert_eq!(
+        Err(ParseError::invalid("temp", "-9999")),
+        parse_temperature("temp", "-9999", TemperatureScale::Tenths)
+    );
+    assert_eq!(
+        Err(ParseError::invalid("temp", "-")),
+        parse_temperature("temp", "-", TemperatureScale::Tenths)
+    );
+
+    let date = NaiveDate::from_ymd_opt(2022, 9, 3).unwrap();
+    let unsigned = "181830 2006N 06141W 9236 00794 0115 201 +173 123041 041 021 002 00";
+    assert_eq!(
+        Err(ParseError::invalid("temp", "201")),
+        HDHALog::parse(&date, unsigned)
+    );
 }
 
 fn parse_wind(www_sss: &str) -> Option<Wind> {
     www_sss
         .parse()
-        .map(|raw: u32| {
-            Wind::with_direction_and_speed(
-                Direction::with_angle(Angle::with_degrees_minutes_seconds(raw / 1000, 0, 0)),
-                Speed::with_knots(raw % 1000),
-            )
-        })
+        // Due north is occasionally sent as 360 rather than 000
+        .map(|raw: u32| Wind::from_degrees_knots(((raw / 1000) % 360) as f64, raw % 1000))
         .ok()
 }
 
+#[test]
+fn test_parse_wind() {
+    assert_eq!(
+        Some(Wind::with_direction_and_speed(
+            crate::measure::WEST,
+            Speed::with_knots(41)
+        )),
+        parse_wind("270041")
+    );
+    assert_eq!(
+        Some(Wind::with_direction_and_speed(
+            crate::measure::NORTH,
+            Speed::with_knots(41)
+        )),
+        parse_wind("360041")
+    );
+    assert_eq!(
+        Some(crate::measure::Direction::from_degrees(1.0)),
+        parse_wind("361041").map(|w| w.direction)
+    );
+    assert_eq!(None, parse_wind("//////"));
+}
+
 fn parse_speed(sss: &str) -> Option<Speed> {
     sss.parse().map(|knots| Speed::with_knots(knots)).ok()
 }
 
-fn parse_rain_rate(ppp: &str) -> Option<RainRate> {
-    ppp.parse()
-        .map(|mm_p_hr| RainRate::with_mm_per_hr(mm_p_hr))
-        .ok()
+/// Parses a rain rate group, which is exactly three digits when present. Anything else is
+/// usually a neighboring group shifted into this column.
+fn parse_rain_rate(ppp: &str) -> Result<Option<RainRate>, ParseError> {
+    if is_missing(ppp) {
+        return Ok(None);
+    }
+    if ppp.len() != 3 || !ppp.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseError::invalid("rain rate", ppp));
+    }
+    Ok(Some(RainRate::with_mm_per_hr(ppp.parse().unwrap())))
+}
+
+fn encode_hhmmss(time: &DateTime<Utc>) -> String {
+    time.format("%H%M%S").to_string()
+}
+
+fn encode_aircraft_pressure(pressure: Pressure, rounding: Rounding) -> String {
+    let tenths = rounding.apply(pressure.microbars(), 100);
+    // Leading 1 is dropped at and above 1000 mb
+    format!("{:04}", tenths % 10000)
+}
+
+#[test]
+fn test_encode_aircraft_pressure() {
+    let pressure = Pressure::with_microbars(1_011_560);
+    assert_eq!(
+        "0116",
+        encode_aircraft_pressure(pressure, Rounding::RoundHalfUp)
+    );
+    assert_eq!(
+        "0115",
+        encode_aircraft_pressure(pressure, Rounding::Truncate)
+    );
+
+    let pressure = Pressure::with_microbars(923_600);
+    assert_eq!(
+        "9236",
+        encode_aircraft_pressure(pressure, Rounding::RoundHalfUp)
+    );
+    assert_eq!(
+        "9236",
+        encode_aircraft_pressure(pressure, Rounding::Truncate)
+    );
+}
+
+fn encode_extrapolated_sfc_pressure(
+    pressure: Option<ExtrapolatedSurfacePressure>,
+    rounding: Rounding,
+) -> String {
+    match pressure {
+        None => "////".to_string(),
+        Some(ExtrapolatedSurfacePressure::ExtrapolatedPressure(p)) => {
+            encode_aircraft_pressure(p, rounding)
+        }
+        Some(ExtrapolatedSurfacePressure::DValue(d)) if d.meters() < 0 => {
+            format!("{:04}", 5000 - d.meters())
+        }
+        Some(ExtrapolatedSurfacePressure::DValue(d)) => format!("{:04}", d.meters()),
+    }
+}
+
+fn encode_temperature(temp: Option<Temperature>, rounding: Rounding) -> String {
+    match temp {
+        None => "////".to_string(),
+        Some(t) => {
+            let tenths = rounding.apply(t.millikelvin() as i32 - 273150, 100);
+            format!("{:+04}", tenths)
+        }
+    }
+}
+
+fn encode_wind(wind: Option<Wind>) -> String {
+    match wind {
+        None => "//////".to_string(),
+        Some(w) => {
+            let (d, _, _) = w.direction.angle().degrees_minutes_seconds();
+            format!("{:03}{:03}", d, w.speed.knots())
+        }
+    }
+}
+
+fn encode_speed(speed: Option<Speed>) -> String {
+    speed.map_or(MISSING.to_string(), |s| format!("{:03}", s.knots()))
+}
+
+fn encode_rain_rate(rain_rate: Option<RainRate>) -> String {
+    rain_rate.map_or(MISSING.to_string(), |r| format!("{:03}", r.mm_per_hr()))
 }